@@ -1,9 +1,10 @@
 --- This visitor is invoked before OPTIONS operation
 --- @param operation Operation
 --- @param extensions table<string, any> # Inline extensions to this object.
+--- @param parameters (QueryParameter|HeaderParameter|PathParameter|CookieParameter)[] # Effective parameters: ones of path item overridden by ones of operation with the same name and location, references are resolved
 --- @param callId string? # some useful identifier of this visitor call
 --- @return WriteOperation[] # Returns the output code and  file name for writing code
-local function visitOptionsOperationStart(operation, extensions, callId)
+local function visitOptionsOperationStart(operation, extensions, parameters, callId)
     return {}
 end
 
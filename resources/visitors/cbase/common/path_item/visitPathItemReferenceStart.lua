@@ -2,9 +2,10 @@
 --- @param pathItemName string|null
 --- @param pathItemReference string
 --- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param resolvedReferences string[] # absolute references followed to the concrete target, more than one if target is reference too
 --- @param callId string? # some useful identifier of this visitor call
 --- @return WriteOperation[] # Returns the output code and  file name for writing code
-local function visitPathItemReferenceStart(pathItemName, pathItemReference, extensions, callId)
+local function visitPathItemReferenceStart(pathItemName, pathItemReference, extensions, resolvedReferences, callId)
     return {}
 end
 
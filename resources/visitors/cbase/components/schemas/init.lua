@@ -6,7 +6,9 @@ M.visitDiscriminator = require("cbase.components.schemas.visitDiscriminator")
 M.visitSchemasStart = require("cbase.components.schemas.visitSchemasStart")
 M.visitSchemaReferenceStart = require("cbase.components.schemas.visitSchemaReferenceStart")
 M.visitSchemaReferenceEnd = require("cbase.components.schemas.visitSchemaReferenceEnd")
+M.visitSchemaCycle = require("cbase.components.schemas.visitSchemaCycle")
 M.visitDefault = require("cbase.components.schemas.visitDefault")
 
+M.keywords = require("cbase.components.schemas.keywords")
 M.kind = require("cbase.components.schemas.kind")
 return M
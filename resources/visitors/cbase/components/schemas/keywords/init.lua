@@ -0,0 +1,23 @@
+local M = {}
+M.visitConst = require("cbase.components.schemas.keywords.visitConst")
+M.visitSchemaExamples = require("cbase.components.schemas.keywords.visitSchemaExamples")
+M.visitContentMediaType = require("cbase.components.schemas.keywords.visitContentMediaType")
+M.visitPrefixItemsStart = require("cbase.components.schemas.keywords.visitPrefixItemsStart")
+M.visitPrefixItemsEnd = require("cbase.components.schemas.keywords.visitPrefixItemsEnd")
+M.visitPrefixItemStart = require("cbase.components.schemas.keywords.visitPrefixItemStart")
+M.visitPrefixItemEnd = require("cbase.components.schemas.keywords.visitPrefixItemEnd")
+M.visitUnevaluatedPropertiesAny = require("cbase.components.schemas.keywords.visitUnevaluatedPropertiesAny")
+M.visitUnevaluatedPropertiesStart = require("cbase.components.schemas.keywords.visitUnevaluatedPropertiesStart")
+M.visitUnevaluatedPropertiesEnd = require("cbase.components.schemas.keywords.visitUnevaluatedPropertiesEnd")
+M.visitDependentSchemasStart = require("cbase.components.schemas.keywords.visitDependentSchemasStart")
+M.visitDependentSchemasEnd = require("cbase.components.schemas.keywords.visitDependentSchemasEnd")
+M.visitDependentSchemaStart = require("cbase.components.schemas.keywords.visitDependentSchemaStart")
+M.visitDependentSchemaEnd = require("cbase.components.schemas.keywords.visitDependentSchemaEnd")
+M.visitIfStart = require("cbase.components.schemas.keywords.visitIfStart")
+M.visitIfEnd = require("cbase.components.schemas.keywords.visitIfEnd")
+M.visitThenStart = require("cbase.components.schemas.keywords.visitThenStart")
+M.visitThenEnd = require("cbase.components.schemas.keywords.visitThenEnd")
+M.visitElseStart = require("cbase.components.schemas.keywords.visitElseStart")
+M.visitElseEnd = require("cbase.components.schemas.keywords.visitElseEnd")
+
+return M
@@ -0,0 +1,10 @@
+--- This visitor is invoked at const value of schema (OpenAPI 3.1)
+--- @param constant any # the only allowed value of schema
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitConst(constant, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitConst", visitConst)
@@ -0,0 +1,11 @@
+--- This visitor is invoked at media type and encoding of string content of schema (OpenAPI 3.1)
+--- @param contentMediaType string? # media type of string content
+--- @param contentEncoding string? # encoding of string content, base64 as example
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitContentMediaType(contentMediaType, contentEncoding, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitContentMediaType", visitContentMediaType)
@@ -0,0 +1,11 @@
+--- This visitor is invoked after processing schema of dependentSchemas element
+--- @param propertyName string # name of property which presence makes schema applied
+--- @param schema ReferenceOr<Schema> #
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitDependentSchemaEnd(propertyName, schema, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitDependentSchemaEnd", visitDependentSchemaEnd, -1)
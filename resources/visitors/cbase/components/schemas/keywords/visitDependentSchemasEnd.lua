@@ -0,0 +1,10 @@
+--- This visitor is invoked after processing dependentSchemas of schema (OpenAPI 3.1)
+--- @param schemas table<string, ReferenceOr<Schema>> # schemas applied when property with such name is present
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitDependentSchemasEnd(schemas, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitDependentSchemasEnd", visitDependentSchemasEnd, -1)
@@ -0,0 +1,10 @@
+--- This visitor is invoked before processing condition schema in `if` (OpenAPI 3.1)
+--- @param schema ReferenceOr<Schema> #
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitIfStart(schema, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitIfStart", visitIfStart, 1)
@@ -0,0 +1,10 @@
+--- This visitor is invoked after processing prefixItems schema element, callId is position of item
+--- @param schema ReferenceOr<Schema> #
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitPrefixItemEnd(schema, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitPrefixItemEnd", visitPrefixItemEnd, -1)
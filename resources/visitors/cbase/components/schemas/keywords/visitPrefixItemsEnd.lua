@@ -0,0 +1,10 @@
+--- This visitor is invoked after processing prefixItems schemas of array (OpenAPI 3.1)
+--- @param schemas ReferenceOr<Schema>[] # schemas of array items by their positions
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitPrefixItemsEnd(schemas, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitPrefixItemsEnd", visitPrefixItemsEnd, -1)
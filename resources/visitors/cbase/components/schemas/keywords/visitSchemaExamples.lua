@@ -0,0 +1,10 @@
+--- This visitor is invoked at examples of schema (OpenAPI 3.1)
+--- @param examples any[] # list of example values of schema
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitSchemaExamples(examples, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitSchemaExamples", visitSchemaExamples)
@@ -0,0 +1,10 @@
+--- This visitor is invoked before processing schema in `then`, applied when `if` is valid (OpenAPI 3.1)
+--- @param schema ReferenceOr<Schema> #
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitThenStart(schema, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitThenStart", visitThenStart, 1)
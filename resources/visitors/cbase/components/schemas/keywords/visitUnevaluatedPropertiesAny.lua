@@ -0,0 +1,10 @@
+--- This visitor handles free-form `unevaluatedProperties` of schema (OpenAPI 3.1)
+--- @param flag boolean # free form of unevaluatedProperties has this value
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitUnevaluatedPropertiesAny(flag, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitUnevaluatedPropertiesAny", visitUnevaluatedPropertiesAny)
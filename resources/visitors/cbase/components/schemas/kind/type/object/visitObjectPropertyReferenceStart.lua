@@ -2,9 +2,10 @@
 --- @param propertyName string|null #
 --- @param schemaReference string #
 --- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param resolvedReferences string[] # absolute references followed to the concrete target, more than one if target is reference too
 --- @param callId string? # some useful identifier of this visitor call
 --- @return WriteOperation[] # Returns the output code and  file name for writing code
-local function visitObjectPropertyReferenceStart(propertyName, schemaReference, extensions, callId)
+local function visitObjectPropertyReferenceStart(propertyName, schemaReference, extensions, resolvedReferences, callId)
     return {}
 end
 
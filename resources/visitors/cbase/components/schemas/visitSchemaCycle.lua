@@ -0,0 +1,13 @@
+--- This visitor is invoked instead of processing schema if reference already visited in current chain of references,
+--- so schema is recursive and target can use reference to already generated type
+--- @param schemaName string|null #
+--- @param schemaReference string #
+--- @param referencesChain string[] # chain of references from first visited to current
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitSchemaCycle(schemaName, schemaReference, referencesChain, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitSchemaCycle", visitSchemaCycle)
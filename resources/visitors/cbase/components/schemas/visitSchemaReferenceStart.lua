@@ -2,9 +2,10 @@
 --- @param schemaName string|null #
 --- @param schemaReference string #
 --- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param resolvedReferences string[] # absolute references followed to the concrete target, more than one if target is reference too
 --- @param callId string? # some useful identifier of this visitor call
 --- @return WriteOperation[] # Returns the output code and  file name for writing code
-local function visitSchemaReferenceStart(schemaName, schemaReference, extensions, callId)
+local function visitSchemaReferenceStart(schemaName, schemaReference, extensions, resolvedReferences, callId)
     -- if we found reference we every time use names from reference or from extension
     GLOBAL_CONTEXT.savedNames:pushAll(GLOBAL_CONTEXT.names.items)
     GLOBAL_CONTEXT.names:clear()
@@ -13,4 +13,5 @@ M.components = require("cbase.components")
 M.info = require("cbase.info")
 M.paths = require("cbase.paths")
 M.tags = require("cbase.tags")
+M.webhooks = require("cbase.webhooks")
 return M
\ No newline at end of file
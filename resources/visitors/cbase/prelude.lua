@@ -33,6 +33,24 @@ if false then
     ---@type string # path to target scripts
     TARGET_PATH = nil
 end
+
+--- Index of whole spec built before any script runs, so visitors can look ahead of the current node.
+--- Schemas of root spec are keyed by local reference like "#/components/schemas/Pet", operations by
+--- operationId or by "METHOD path" if it is absent.
+--- @class SpecIndex
+--- @field schemas table<string, table> # reference, name, schema, parents, children, uses, usedBySchemas, usedByOperations
+--- @field operations table<string, table> # operationId, method, path, webhook, tags, operation, uses
+--- @field tags table<string, string[]> # keys of operations by tag
+--- @field getSchema fun(reference: string): table|nil # schema by any reference of root spec
+--- @field getOperation fun(key: string): table|nil # operation by operationId or "METHOD path"
+--- @field getOperationsByTag fun(tag: string): table[] # operations with tag
+--- @field get fun(pointer: string): any|nil # node of root spec by JSON pointer, "#" prefix is optional
+--- @field resolve fun(reference: string): any, string # final target of reference and its absolute reference, remote documents are fetched as in translation
+--- @field raw fun(): table # whole root spec
+if false then
+    ---@type SpecIndex
+    SPEC = nil
+end
 ---------------------------------------------------------------------------------------------------------
 
 --- TYPES -----------------------------------------------------------------------------------------------
@@ -1192,11 +1210,26 @@ end
 --- @field APPEND string # Add something at end of file, if file does not exists create it
 --- @field PREPEND string # Add something at start of file, if file does not exists create it
 --- @field REMOVE string # Remove some model file if it exists or no op
+--- @field OVERWRITE string # Replace content of file, if file does not exists create it
+--- @field CREATE_IF_ABSENT string # Create file only if it does not exists, for scaffolding edited by users
 WriteMode = {}
 
 WriteMode.APPEND = "APPEND"
 WriteMode.PREPEND = "PREPEND"
 WriteMode.REMOVE = "REMOVE"
+WriteMode.OVERWRITE = "OVERWRITE"
+WriteMode.CREATE_IF_ABSENT = "CREATE_IF_ABSENT"
+
+--- Control of traversal, returned by visitor in list together with WriteOperation
+--- @class VisitDirective
+--- @field CONTINUE table # Visit children of node as usual
+--- @field SKIP_CHILDREN table # Do not visit children of node, end visitor of node is still called
+--- @field STOP table # Stop traversal of spec, code written so far is kept
+VisitDirective = {}
+
+VisitDirective.CONTINUE = { directive = "CONTINUE" }
+VisitDirective.SKIP_CHILDREN = { directive = "SKIP_CHILDREN" }
+VisitDirective.STOP = { directive = "STOP" }
 
 --- Enum emulation for predefined extensions
 --- @class Extensions
@@ -1242,6 +1275,8 @@ end
 --- @field code string generated code
 --- @field file string output file name
 --- @field mode string output file name
+--- @field permissions string? unix file mode as octal string, for example "755"
+--- @field base64 string? binary content encoded as base64, used instead of code
 WriteOperation = {}
 WriteOperation.__index = WriteOperation
 
@@ -1308,6 +1343,21 @@ function WriteOperation.new_with_mode(code, modelName, mode)
     return instance
 end
 
+--- @param base64 string # binary content encoded as base64
+--- @param fileName string # output file name
+--- @param mode string # one of WriteMode
+--- @param permissions string? # unix file mode as octal string
+function WriteOperation.new_binary(base64, fileName, mode, permissions)
+    assert(not is_string_blank(fileName), "empty fileName")
+    local instance = setmetatable({}, WriteOperation)
+    instance.code = nil
+    instance.base64 = base64
+    instance.file = fileName
+    instance.mode = mode
+    instance.permissions = permissions
+    return instance
+end
+
 --- Create new write operation from old to new model file
 --- @param modelName string # data for generate file name
 --- @param writeOperation WriteOperation # produced code
@@ -1319,6 +1369,8 @@ function WriteOperation.from(writeOperation, modelName)
     instance.code = writeOperation.code
     instance.file = modelName .. ".java"
     instance.mode = writeOperation.mode
+    instance.permissions = writeOperation.permissions
+    instance.base64 = writeOperation.base64
     return instance
 end
 
@@ -1,5 +1,5 @@
 --- This visitor is invoked at the start of OpenAPI scpec
---- @param version string # OpenAPI version
+--- @param version string? # OpenAPI version, nil for JSON Schema input kind
 --- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
 --- @param callId string? # some useful identifier of this visitor call
 --- @return WriteOperation[] # Returns the output code and  file name for writing code
@@ -0,0 +1,7 @@
+local M = {}
+M.visitWebhooksStart = require("cbase.webhooks.visitWebhooksStart")
+M.visitWebhooksEnd = require("cbase.webhooks.visitWebhooksEnd")
+M.visitWebhookStart = require("cbase.webhooks.visitWebhookStart")
+M.visitWebhookEnd = require("cbase.webhooks.visitWebhookEnd")
+
+return M
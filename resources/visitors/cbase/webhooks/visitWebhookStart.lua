@@ -0,0 +1,11 @@
+--- This visitor is invoked before processing path item of webhook
+--- @param webhookName string # name of webhook
+--- @param pathItem ReferenceOr<PathItem> # requests which API provider initiates
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitWebhookStart(webhookName, pathItem, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitWebhookStart", visitWebhookStart, 1)
@@ -0,0 +1,10 @@
+--- This visitor is invoked at end of processing webhooks in spec (OpenAPI 3.1)
+--- @param webhooks table<string, ReferenceOr<PathItem>> # path items by webhook names
+--- @param extensions table # table with free form with "x-" OpenAPI extensions for this level of spec
+--- @param callId string? # some useful identifier of this visitor call
+--- @return WriteOperation[] # Returns the output code and  file name for writing code
+local function visitWebhooksEnd(webhooks, extensions, callId)
+    return {}
+end
+
+return functionCallAndLog("visitWebhooksEnd", visitWebhooksEnd, -1)
@@ -1,15 +1,30 @@
-use std::{fmt::Display, ops::Add, path::Path};
+use std::{fmt::Display, ops::Add};
 
 use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use log::info;
 use mlua::LuaSerdeExt;
 use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 use crate::{
     holders::context::get_lua_vm,
-    services::{code, scripts},
+    services::{code, json_schema, lint, scripts},
+    structs::common::{TraversalStopped, VisitOutput, VisitorOutput},
 };
 
+/// Control of traversal returned by visitor, the strongest one is taken if several are returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum VisitDirective {
+    #[serde(rename = "CONTINUE")]
+    Continue,
+    /// Children of node are not visited, but end visitor of node is called
+    #[serde(rename = "SKIP_CHILDREN")]
+    SkipChildren,
+    #[serde(rename = "STOP")]
+    Stop,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WriteMode {
     #[serde(rename = "APPEND")]
@@ -18,6 +33,32 @@ pub enum WriteMode {
     Prepend,
     #[serde(rename = "REMOVE")]
     Remove,
+    #[serde(rename = "OVERWRITE")]
+    Overwrite,
+    #[serde(rename = "CREATE_IF_ABSENT")]
+    CreateIfAbsent,
+}
+
+/// Kind of document given to translation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InputKind {
+    Openapi,
+    JsonSchema,
+}
+
+/// Severity of lint rule, error fails lint command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum LintSeverity {
+    Off,
+    Warn,
+    Error,
+}
+
+/// What to do with components of merged specs which have the same name but different content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MergeConflict {
+    Report,
+    Rename,
 }
 
 #[derive(Clone, Serialize, Deserialize, EnumIter, PartialEq, Eq)]
@@ -76,6 +117,10 @@ pub enum Script {
     VisitParametersEnd,
     VisitPathsStart,
     VisitPathsEnd,
+    VisitWebhooksStart,
+    VisitWebhooksEnd,
+    VisitWebhookStart,
+    VisitWebhookEnd,
     VisitResponseReferenceStart,
     VisitResponseReferenceEnd,
     VisitResponseStart,
@@ -137,6 +182,7 @@ pub enum Script {
     VisitSchemaEnd,
     VisitSchemaReferenceStart,
     VisitSchemaReferenceEnd,
+    VisitSchemaCycle,
     VisitDefault,
     VisitDiscriminator,
     VisitSpecStart,
@@ -190,23 +236,66 @@ pub enum Script {
     VisitAnyOfEnd,
     VisitAnyOfElementStart,
     VisitAnyOfElementEnd,
+    VisitConst,
+    VisitSchemaExamples,
+    VisitContentMediaType,
+    VisitPrefixItemsStart,
+    VisitPrefixItemsEnd,
+    VisitPrefixItemStart,
+    VisitPrefixItemEnd,
+    VisitUnevaluatedPropertiesAny,
+    VisitUnevaluatedPropertiesStart,
+    VisitUnevaluatedPropertiesEnd,
+    VisitDependentSchemasStart,
+    VisitDependentSchemasEnd,
+    VisitDependentSchemaStart,
+    VisitDependentSchemaEnd,
+    VisitIfStart,
+    VisitIfEnd,
+    VisitThenStart,
+    VisitThenEnd,
+    VisitElseStart,
+    VisitElseEnd,
 }
 
 impl Script {
     pub fn call_with_descriptor<T>(
         &self,
         call_id: Option<&str>,
-        out_path: &Path,
+        output: &VisitOutput,
         args: &T,
     ) -> Result<()>
     where
         T: Serialize,
     {
+        self.call_with_directive(call_id, output, args).map(|_| ())
+    }
+
+    /// Directive returned by visitor together with write operations, stop of traversal is returned
+    /// as `TraversalStopped` error after the operations are written
+    pub fn call_with_directive<T>(
+        &self,
+        call_id: Option<&str>,
+        output: &VisitOutput,
+        args: &T,
+    ) -> Result<VisitDirective>
+    where
+        T: Serialize,
+    {
+        let mut args_value = serde_json::to_value(args)?;
+        json_schema::strip_keywords_extension(&mut args_value);
+
+        let out_path = match output {
+            VisitOutput::Code(out_path) => out_path,
+            VisitOutput::Lint(linter) => {
+                lint::check_visit(linter, self, &args_value)?;
+                return Ok(VisitDirective::Continue);
+            }
+        };
+
         let lua_vm = get_lua_vm();
         let func = scripts::get_lua_function(self, &lua_vm)?;
 
-        let args_value = serde_json::to_value(args)?;
-
         let args_vec = if args_value.is_array() {
             args_value
                 .as_array()
@@ -304,9 +393,22 @@ impl Script {
             )
         })?;
 
-        code::save_code(out_path, lua_vm.from_value(code)?)?;
+        let outputs: Vec<VisitorOutput> = lua_vm.from_value(code)?;
+        let mut directive = VisitDirective::Continue;
+        let mut codes = Vec::with_capacity(outputs.len());
+        for output in outputs {
+            match output {
+                VisitorOutput::Directive { directive: it } => directive = directive.max(it),
+                VisitorOutput::Code(code) => codes.push(code),
+            }
+        }
+        code::save_code(out_path, codes)?;
 
-        Ok(())
+        if directive == VisitDirective::Stop {
+            info!("Traversal stopped by visitor {}", self);
+            return Err(anyhow!(TraversalStopped));
+        }
+        Ok(directive)
     }
 
     pub fn call_func(&self, call_id: Option<&str>) -> Result<()> {
@@ -563,11 +665,16 @@ impl From<&Script> for &'static str {
             Script::VisitDeleteOperationEnd => "return VISITORS.common.operation.visitDeleteOperationEnd",
             Script::VisitPathsStart => "return VISITORS.paths.visitPathsStart",
             Script::VisitPathsEnd => "return VISITORS.paths.visitPathsEnd",
+            Script::VisitWebhooksStart => "return VISITORS.webhooks.visitWebhooksStart",
+            Script::VisitWebhooksEnd => "return VISITORS.webhooks.visitWebhooksEnd",
+            Script::VisitWebhookStart => "return VISITORS.webhooks.visitWebhookStart",
+            Script::VisitWebhookEnd => "return VISITORS.webhooks.visitWebhookEnd",
             //References
             Script::VisitResponseReferenceStart => "return VISITORS.components.responses.visitResponseReferenceStart",
             Script::VisitResponseReferenceEnd => "return VISITORS.components.responses.visitResponseReferenceEnd",
             Script::VisitSchemaReferenceStart => "return VISITORS.components.schemas.visitSchemaReferenceStart",
             Script::VisitSchemaReferenceEnd => "return VISITORS.components.schemas.visitSchemaReferenceEnd",
+            Script::VisitSchemaCycle => "return VISITORS.components.schemas.visitSchemaCycle",
             Script::VisitExampleReferenceStart => "return VISITORS.common.examples.visitExampleReferenceStart",
             Script::VisitExampleReferenceEnd => "return VISITORS.common.examples.visitExampleReferenceEnd",
             Script::VisitRequestBodyReferenceStart => {
@@ -609,6 +716,36 @@ impl From<&Script> for &'static str {
             Script::VisitAnyOfElementEnd => "return VISITORS.components.schemas.kind.anyOf.visitAnyOfElementEnd",
             Script::VisitOneOfElementStart => "return VISITORS.components.schemas.kind.oneOf.visitOneOfElementStart",
             Script::VisitOneOfElementEnd => "return VISITORS.components.schemas.kind.oneOf.visitOneOfElementEnd",
+            Script::VisitConst => "return VISITORS.components.schemas.keywords.visitConst",
+            Script::VisitSchemaExamples => "return VISITORS.components.schemas.keywords.visitSchemaExamples",
+            Script::VisitContentMediaType => "return VISITORS.components.schemas.keywords.visitContentMediaType",
+            Script::VisitPrefixItemsStart => "return VISITORS.components.schemas.keywords.visitPrefixItemsStart",
+            Script::VisitPrefixItemsEnd => "return VISITORS.components.schemas.keywords.visitPrefixItemsEnd",
+            Script::VisitPrefixItemStart => "return VISITORS.components.schemas.keywords.visitPrefixItemStart",
+            Script::VisitPrefixItemEnd => "return VISITORS.components.schemas.keywords.visitPrefixItemEnd",
+            Script::VisitUnevaluatedPropertiesAny => {
+                "return VISITORS.components.schemas.keywords.visitUnevaluatedPropertiesAny"
+            }
+            Script::VisitUnevaluatedPropertiesStart => {
+                "return VISITORS.components.schemas.keywords.visitUnevaluatedPropertiesStart"
+            }
+            Script::VisitUnevaluatedPropertiesEnd => {
+                "return VISITORS.components.schemas.keywords.visitUnevaluatedPropertiesEnd"
+            }
+            Script::VisitDependentSchemasStart => {
+                "return VISITORS.components.schemas.keywords.visitDependentSchemasStart"
+            }
+            Script::VisitDependentSchemasEnd => "return VISITORS.components.schemas.keywords.visitDependentSchemasEnd",
+            Script::VisitDependentSchemaStart => {
+                "return VISITORS.components.schemas.keywords.visitDependentSchemaStart"
+            }
+            Script::VisitDependentSchemaEnd => "return VISITORS.components.schemas.keywords.visitDependentSchemaEnd",
+            Script::VisitIfStart => "return VISITORS.components.schemas.keywords.visitIfStart",
+            Script::VisitIfEnd => "return VISITORS.components.schemas.keywords.visitIfEnd",
+            Script::VisitThenStart => "return VISITORS.components.schemas.keywords.visitThenStart",
+            Script::VisitThenEnd => "return VISITORS.components.schemas.keywords.visitThenEnd",
+            Script::VisitElseStart => "return VISITORS.components.schemas.keywords.visitElseStart",
+            Script::VisitElseEnd => "return VISITORS.components.schemas.keywords.visitElseEnd",
         }
     }
 }
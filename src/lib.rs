@@ -1,20 +1,34 @@
 use ansi_term::Color;
 use anyhow::{anyhow, Context, Result};
-use enums::common::Script;
+use enums::common::{InputKind, LintSeverity, MergeConflict, Script};
 use holders::context::{get_lua_vm, CLI, LOG_CONTEXT};
 use serde_json::Value;
 use services::scripts;
 use std::path::PathBuf;
 use std::{collections::HashMap, path::Path};
+use structs::common::HttpHeader;
 use strum::IntoEnumIterator;
 
-use clap::{ArgAction, Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 pub mod services {
+    pub mod bundle;
     pub mod cli;
     pub mod code;
     pub mod comparators;
+    pub mod dereference;
+    pub mod filters;
+    pub mod http;
+    pub mod indexing;
+    pub mod json_schema;
+    pub mod lint;
+    pub mod locations;
+    pub mod merge;
+    pub mod overlay;
     pub mod references;
     pub mod scripts;
+    pub mod spec_keys;
+    pub mod swagger;
+    pub mod validation;
     pub mod visitors;
 }
 
@@ -38,6 +52,39 @@ fn parse_parameters_val(value: &str) -> Result<Value> {
     Ok(serde_json::from_str(value)?)
 }
 
+fn parse_ref_map_val(value: &str) -> Result<(String, PathBuf)> {
+    value
+        .split_once('=')
+        .map(|(prefix, path)| (prefix.to_owned(), PathBuf::from(path)))
+        .ok_or_else(|| anyhow!("Expected URL_PREFIX=PATH but found [{}]", value))
+}
+
+fn parse_http_header_val(value: &str) -> Result<HttpHeader> {
+    value
+        .split_once('=')
+        .and_then(|(host, header)| {
+            header.split_once(':').map(|(name, env_var)| HttpHeader {
+                host: host.to_owned(),
+                name: name.to_owned(),
+                env_var: env_var.to_owned(),
+            })
+        })
+        .filter(|it| !it.host.is_empty() && !it.name.is_empty() && !it.env_var.is_empty())
+        .ok_or_else(|| anyhow!("Expected HOST=HEADER:ENV_VAR but found [{}]", value))
+}
+
+fn parse_lint_rule_val(value: &str) -> Result<(String, LintSeverity)> {
+    value
+        .split_once('=')
+        .and_then(|(rule, severity)| {
+            LintSeverity::from_str(severity, true)
+                .ok()
+                .map(|it| (rule.to_owned(), it))
+        })
+        .filter(|(rule, _)| !rule.is_empty())
+        .ok_or_else(|| anyhow!("Expected RULE=off|warn|error but found [{}]", value))
+}
+
 #[derive(Parser)]
 #[command(version, about="OpenAPI v3 translator", long_about = None)]
 pub struct Cli {
@@ -53,17 +100,146 @@ pub struct Cli {
         short = 'a',
         long = "target-scripts",
         value_name = "TARGET_SCRIPTS_PATH",
-        help = "Since visitors can be reused, the target dir contains in a separate script that runs at the start of the translation, where functions and modules that will be used in the general set of visitors to implement specific types of translation can be defined"
+        help = "Since visitors can be reused, the target dir contains in a separate script that runs at the start of the translation, where functions and modules that will be used in the general set of visitors to implement specific types of translation can be defined. Required by translate and test commands"
     )]
-    pub target_scripts_path: PathBuf,
+    pub target_scripts_path: Option<PathBuf>,
 
     #[arg(
         short = 'i',
         long = "visitors-scripts",
         value_name = "VISITORS_SCRIPTS_PATH",
-        help = "The base directory for all visitors scripts, since for many types of translators, the final result only differs in specific small elements but is structurally similar, a common set of visitors can be used for different translation purposes"
+        help = "The base directory for all visitors scripts, since for many types of translators, the final result only differs in specific small elements but is structurally similar, a common set of visitors can be used for different translation purposes. Required by translate and test commands"
+    )]
+    pub visitors_scripts_path: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Fail on any remote $ref which is not mapped to local path by --ref-map or found in --refs-cache"
+    )]
+    pub offline: bool,
+
+    #[arg(
+        long = "ref-map",
+        value_name = "URL_PREFIX=PATH",
+        action = ArgAction::Append,
+        value_parser = parse_ref_map_val,
+        help = "Rewrite remote $ref URL prefix to local path, for example https://schemas.example.com/=./vendor/schemas/ (option can be set multiple times)"
+    )]
+    pub ref_map: Vec<(String, PathBuf)>,
+
+    #[arg(
+        long = "refs-cache",
+        value_name = "REFS_CACHE_PATH",
+        help = "Directory to cache fetched remote documents, keyed by URL. Cached documents are used instead of remote requests"
+    )]
+    pub refs_cache_path: Option<PathBuf>,
+
+    #[arg(
+        long = "http-header",
+        value_name = "HOST=HEADER:ENV_VAR",
+        action = ArgAction::Append,
+        value_parser = parse_http_header_val,
+        help = "Header sent with remote $ref requests to HOST (host or host:port), value is taken from environment variable ENV_VAR, for example api.example.com=Authorization:API_TOKEN (option can be set multiple times)"
+    )]
+    pub http_headers: Vec<HttpHeader>,
+
+    #[arg(
+        long = "http-timeout",
+        value_name = "SECONDS",
+        default_value_t = 30,
+        help = "Timeout of a remote $ref request"
+    )]
+    pub http_timeout: u64,
+
+    #[arg(
+        long = "http-retries",
+        value_name = "COUNT",
+        default_value_t = 0,
+        help = "Count of retries of a remote $ref request failed by connection error, timeout, 429 or 5xx status"
     )]
-    pub visitors_scripts_path: PathBuf,
+    pub http_retries: u32,
+
+    #[arg(
+        long = "http-retry-backoff",
+        value_name = "MILLIS",
+        default_value_t = 500,
+        help = "Delay before first retry, doubled for every next retry"
+    )]
+    pub http_retry_backoff: u64,
+
+    #[arg(
+        long = "http-ca-cert",
+        value_name = "PEM_PATH",
+        help = "Additional CA certificates bundle in PEM format to trust for remote $ref requests"
+    )]
+    pub http_ca_cert_path: Option<PathBuf>,
+
+    #[arg(
+        long = "http-proxy",
+        value_name = "PROXY_URL",
+        help = "Proxy for remote $ref requests, for example http://proxy.local:3128"
+    )]
+    pub http_proxy: Option<String>,
+
+    #[arg(
+        long = "overlay",
+        value_name = "OVERLAY_PATH",
+        action = ArgAction::Append,
+        help = "OpenAPI Overlay 1.0 document applied to spec before translation, overlays are applied in the given order (option can be set multiple times)"
+    )]
+    pub overlays: Vec<PathBuf>,
+
+    #[arg(
+        long = "no-validate",
+        help = "Translate spec without validation of refs, operationIds, path params, required properties and discriminator mappings"
+    )]
+    pub no_validate: bool,
+
+    #[arg(
+        long = "include-tag",
+        value_name = "TAG",
+        action = ArgAction::Append,
+        help = "Translate only operations with this tag, replaces includeTags of x-ot-filters in spec (option can be set multiple times)"
+    )]
+    pub include_tags: Vec<String>,
+
+    #[arg(
+        long = "exclude-tag",
+        value_name = "TAG",
+        action = ArgAction::Append,
+        help = "Do not translate operations with this tag, replaces excludeTags of x-ot-filters in spec (option can be set multiple times)"
+    )]
+    pub exclude_tags: Vec<String>,
+
+    #[arg(
+        long = "include-path",
+        value_name = "GLOB",
+        action = ArgAction::Append,
+        help = "Translate only paths matched by glob pattern, for example /users/*, replaces includePaths of x-ot-filters in spec (option can be set multiple times)"
+    )]
+    pub include_paths: Vec<String>,
+
+    #[arg(
+        long = "include-operation",
+        value_name = "OPERATION_ID",
+        action = ArgAction::Append,
+        help = "Translate only operation with this operationId, replaces includeOperations of x-ot-filters in spec (option can be set multiple times)"
+    )]
+    pub include_operations: Vec<String>,
+
+    #[arg(
+        long = "include-schema",
+        value_name = "SCHEMA_NAME",
+        action = ArgAction::Append,
+        help = "Translate only this schema of components, replaces includeSchemas of x-ot-filters in spec (option can be set multiple times)"
+    )]
+    pub include_schemas: Vec<String>,
+
+    #[arg(
+        long = "prune-schemas",
+        help = "Translate only schemas of components reachable from included operations and schemas"
+    )]
+    pub prune_schemas: bool,
 
     #[command(subcommand, help = "Action to execution")]
     pub command: Commands,
@@ -73,20 +249,29 @@ impl Cli {
     pub fn get_tests_dir(&self) -> Option<&PathBuf> {
         match &self.command {
             Commands::Test { tests, .. } => Some(tests),
-            Commands::Translate { .. } => None,
+            Commands::Translate { .. }
+            | Commands::Bundle { .. }
+            | Commands::Dereference { .. }
+            | Commands::Validate { .. }
+            | Commands::Lint { .. } => None,
         }
     }
 
-    pub fn get_visitors_dir(&self) -> &PathBuf {
-        &self.visitors_scripts_path
+    /// Scripts are needed only by commands which translate
+    pub fn get_visitors_dir(&self) -> Result<&PathBuf> {
+        self.visitors_scripts_path
+            .as_ref()
+            .ok_or_else(|| anyhow!("Visitors scripts path (--visitors-scripts) is not set"))
     }
 
-    pub fn get_target_dir(&self) -> &PathBuf {
-        &self.target_scripts_path
+    pub fn get_target_dir(&self) -> Result<&PathBuf> {
+        self.target_scripts_path
+            .as_ref()
+            .ok_or_else(|| anyhow!("Target scripts path (--target-scripts) is not set"))
     }
 }
 
-#[derive(Debug, Subcommand)]
+#[derive(Debug, Clone, Subcommand)]
 pub enum Commands {
     Test {
         #[arg(
@@ -112,9 +297,12 @@ pub enum Commands {
             short,
             long,
             value_name = "OPENAPI",
-            help = "OpenAPI spec to translation"
+            required = true,
+            action = ArgAction::Append,
+            num_args = 1,
+            help = "OpenAPI spec to translation, path which is not a file is taken as glob pattern. Several specs are merged into one before translation (option can be set multiple times)"
         )]
-        spec: PathBuf,
+        spec: Vec<PathBuf>,
         #[arg(
             short,
             long,
@@ -131,8 +319,98 @@ pub enum Commands {
             help = "Compare the files in the EXPECTED directory with those in OUTPATH. If differences are found, exit with code 1 and display the diff"
         )]
         expected: Option<PathBuf>,
+        #[arg(
+            long = "input-kind",
+            value_enum,
+            default_value_t = InputKind::Openapi,
+            help = "Kind of SPEC, json-schema is a bare JSON Schema document or directory of them, visited as schemas named by file names"
+        )]
+        input_kind: InputKind,
+        #[arg(
+            long = "merge-conflict",
+            value_enum,
+            default_value_t = MergeConflict::Report,
+            help = "What to do with components of several specs which have the same name but different content, rename appends a number to the name of later one"
+        )]
+        merge_conflict: MergeConflict,
         test_name: Option<String>,
     },
+    #[command(
+        about = "Pull every external $ref target into components and write spec as a single YAML or JSON file"
+    )]
+    Bundle {
+        #[arg(short, long, value_name = "OPENAPI", help = "OpenAPI spec to bundle")]
+        spec: PathBuf,
+        #[arg(
+            short,
+            long,
+            value_name = "OUTFILE",
+            help = "Path to write bundled spec, written as JSON if extension is .json, otherwise as YAML"
+        )]
+        out: PathBuf,
+    },
+    #[command(
+        about = "Inline every $ref target, refs closing a cycle are left local, and write spec as a single YAML or JSON file"
+    )]
+    Dereference {
+        #[arg(
+            short,
+            long,
+            value_name = "OPENAPI",
+            help = "OpenAPI spec to dereference"
+        )]
+        spec: PathBuf,
+        #[arg(
+            short,
+            long,
+            value_name = "OUTFILE",
+            help = "Path to write dereferenced spec, written as JSON if extension is .json, otherwise as YAML"
+        )]
+        out: PathBuf,
+    },
+    #[command(
+        about = "Check spec for unresolvable refs, duplicate operationIds, undeclared path params, unknown required properties and invalid discriminator mappings, all problems are reported at once"
+    )]
+    Validate {
+        #[arg(short, long, value_name = "OPENAPI", help = "OpenAPI spec to validate")]
+        spec: PathBuf,
+    },
+    #[command(
+        about = "Check spec by built-in and Lua lint rules over the translation traversal, fails if a rule with error severity is broken"
+    )]
+    Lint {
+        #[arg(
+            short,
+            long,
+            value_name = "OPENAPI",
+            required = true,
+            action = ArgAction::Append,
+            num_args = 1,
+            help = "OpenAPI spec to lint, path which is not a file is taken as glob pattern. Several specs are merged into one before lint (option can be set multiple times)"
+        )]
+        spec: Vec<PathBuf>,
+        #[arg(
+            long = "rule",
+            value_name = "RULE=SEVERITY",
+            action = ArgAction::Append,
+            value_parser = parse_lint_rule_val,
+            help = "Severity of rule: off, warn or error. Built-in rules are operation-description, operation-operationId, parameter-description, schema-description, property-camel-case and no-inline-schema, all of them warn by default (option can be set multiple times)"
+        )]
+        rules: Vec<(String, LintSeverity)>,
+        #[arg(
+            long = "lua-rules",
+            value_name = "LUA_RULES_PATH",
+            help = "Lua script returning table of rules by name, every rule has name of visitor, check function called with arguments of the visitor and returning nil, message or list of messages, and optional severity. Rules can use SPEC global as visitors"
+        )]
+        lua_rules: Option<PathBuf>,
+        #[arg(
+            long = "merge-conflict",
+            value_enum,
+            default_value_t = MergeConflict::Report,
+            help = "What to do with components of several specs which have the same name but different content, rename appends a number to the name of later one"
+        )]
+        merge_conflict: MergeConflict,
+    },
 }
 
 pub fn init_logger() -> Result<()> {
@@ -172,8 +450,8 @@ pub fn init_logger() -> Result<()> {
 
 pub fn check_scripts() -> Result<()> {
     let mut scripts_files: HashMap<String, String> = HashMap::new();
-    let visitors = CLI.get_visitors_dir();
-    let target = CLI.get_target_dir();
+    let visitors = CLI.get_visitors_dir()?;
+    let target = CLI.get_target_dir()?;
 
     check_script(Script::Target, target, &mut scripts_files)?;
     for variant in Script::iter().filter(|it| *it != Script::Target) {
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use log::{error, info};
 use openapi_translator::enums::common::Script;
+use openapi_translator::holders::context::CLI;
 use openapi_translator::init_logger;
 use openapi_translator::services::cli;
 
@@ -8,7 +9,11 @@ fn main() -> Result<()> {
     init_logger()?;
     info!("================================================ TRANSLATION START ================================================");
     cli::visit_commands().inspect_err(|err| {
-        if let Err(error) = Script::ErrorHandler.call_func(Some(&format!("{:?}", err))) {
+        // Error handler is defined by target scripts, commands without them only return error
+        if CLI.target_scripts_path.is_none() {
+            return;
+        }
+        if let Err(error) = Script::ErrorHandler.call_func(Some(&format!("{:#}", err))) {
             error!("errorHandler function not called from LUA vm: [{}]", error)
         }
     })?;
@@ -0,0 +1,393 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    path::Path,
+};
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info};
+use serde_json::{Map, Value};
+
+use crate::{
+    holders::context::EXTENSION_FOR_NAME,
+    services::{
+        references,
+        spec_keys::{
+            self, COMPONENTS_KEY, OPERATION_KEYS, PARAMETERS_KEY, PATHS_KEY, REFERENCE_KEY,
+            SCHEMAS_KEY, SUBSCHEMA_KEYS, SUBSCHEMA_LISTS_KEYS, SUBSCHEMA_MAPS_KEYS, WEBHOOKS_KEY,
+        },
+    },
+    structs::common::ParsedSpec,
+    Commands,
+};
+
+const PATH_ITEMS_KIND: &str = "pathItems";
+/// Kind of object by its structural position in spec
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    PathItem,
+    Operation,
+    Parameter,
+    RequestBody,
+    Response,
+    MediaType,
+    Encoding,
+    Header,
+    Example,
+    Link,
+    Callback,
+    SecurityScheme,
+    Schema,
+}
+
+/// Structural position of node, component kind of reference is taken from it
+#[derive(Clone, Copy, PartialEq)]
+enum Node {
+    Spec,
+    Components,
+    Item(Kind),
+    /// Map of objects with names chosen by spec author
+    Map(Kind),
+    List(Kind),
+    /// Instance data or node of unknown structure
+    Other,
+}
+
+enum BundledReference {
+    Local(String),
+    Inline(Value),
+}
+
+struct Bundler<'a> {
+    parsed_spec: &'a ParsedSpec,
+    root_document: String,
+    /// Absolute reference to kind and name of imported component
+    imported: HashMap<String, (String, String)>,
+    taken_names: HashSet<(String, String)>,
+    components: Map<String, Value>,
+}
+
+pub fn bundle_command(command: &Commands) -> Result<()> {
+    if let Commands::Bundle {
+        spec: spec_path,
+        out: out_path,
+    } = command
+    {
+        info!("Bundle start for [{:?}]", spec_path);
+
+        let parsed_spec = references::load_spec(spec_path)?;
+        let bundled = bundle_spec(&parsed_spec)?;
+        write_spec(out_path, &bundled)?;
+
+        info!("Bundle end, written to [{:?}]", out_path);
+        Ok(())
+    } else {
+        Err(anyhow!("Expected a Bundle command"))
+    }
+}
+
+pub fn bundle_spec(parsed_spec: &ParsedSpec) -> Result<Value> {
+    let root_uri = references::absolute_reference("", parsed_spec)?;
+    let (root_document, _) = references::split_reference(&root_uri);
+
+    let mut bundled = parsed_spec.spec.as_ref().clone();
+
+    let taken_names = bundled
+        .get(COMPONENTS_KEY)
+        .and_then(Value::as_object)
+        .map(|components| {
+            components
+                .iter()
+                .filter_map(|(kind, it)| it.as_object().map(|named| (kind, named)))
+                .flat_map(|(kind, named)| named.keys().map(|name| (kind.clone(), name.clone())))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut bundler = Bundler {
+        parsed_spec,
+        root_document: root_document.to_owned(),
+        imported: HashMap::new(),
+        taken_names,
+        components: Map::new(),
+    };
+    bundler.bundle_value(&mut bundled, Node::Spec)?;
+
+    if !bundler.components.is_empty() {
+        let components = bundled
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("Spec is not an object"))?
+            .entry(COMPONENTS_KEY)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("Spec components is not an object"))?;
+
+        for (kind, named) in bundler.components {
+            let target = components
+                .entry(kind.clone())
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .ok_or_else(|| anyhow!("Spec components [{}] is not an object", kind))?;
+            if let Value::Object(named) = named {
+                target.extend(named);
+            }
+        }
+    }
+
+    Ok(bundled)
+}
+
+pub fn write_spec(out_path: &Path, spec: &Value) -> Result<()> {
+    if let Some(parent) = out_path.parent().filter(|it| !it.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Could not create directory [{:?}]", parent))?;
+    }
+    let file =
+        File::create(out_path).with_context(|| format!("Could not create [{:?}]", out_path))?;
+
+    if out_path.extension().and_then(|it| it.to_str()) == Some("json") {
+        serde_json::to_writer_pretty(file, spec)
+            .with_context(|| format!("Could not write spec as json [{:?}]", out_path))
+    } else {
+        serde_yaml::to_writer(file, spec)
+            .with_context(|| format!("Could not write spec as yaml [{:?}]", out_path))
+    }
+}
+
+impl Bundler<'_> {
+    fn bundle_value(&mut self, value: &mut Value, node: Node) -> Result<()> {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get(REFERENCE_KEY) {
+                    let reference = reference.clone();
+                    match self.bundle_reference(&reference, node)? {
+                        BundledReference::Local(local) => {
+                            map.insert(REFERENCE_KEY.to_owned(), Value::String(local));
+                        }
+                        BundledReference::Inline(content) => *value = content,
+                    }
+                } else {
+                    for (key, child) in map.iter_mut() {
+                        let child_node = get_child_node(node, key);
+                        if child_node != Node::Other || node == Node::Other {
+                            self.bundle_value(child, child_node)?;
+                        }
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let item_node = match node {
+                    Node::List(item) => Node::Item(item),
+                    // Tuple form of items
+                    Node::Item(Kind::Schema) => node,
+                    _ => Node::Other,
+                };
+                for item in items.iter_mut() {
+                    self.bundle_value(item, item_node)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn bundle_reference(&mut self, reference: &str, node: Node) -> Result<BundledReference> {
+        let absolute_uri = references::absolute_reference(reference, self.parsed_spec)?;
+        let (document, fragment) = references::split_reference(&absolute_uri);
+
+        if document == self.root_document {
+            return Ok(BundledReference::Local(format!("#{}", fragment)));
+        }
+
+        if let Some((kind, name)) = self.imported.get(&absolute_uri) {
+            return Ok(BundledReference::Local(get_local_reference(kind, name)));
+        }
+
+        let (kind, item) = get_component_kind(fragment, node);
+        let mut content = references::resolve_value(&absolute_uri, self.parsed_spec)?;
+
+        // Alias like Swagger 2.0 pointer of upgraded document is imported as its target
+        if let Value::Object(map) = &content {
+            if map.len() == 1 && map.contains_key(REFERENCE_KEY) {
+                let chain = references::resolve_reference_chain(&absolute_uri, self.parsed_spec)?;
+                return self.bundle_reference(&chain[1], node);
+            }
+        }
+
+        if kind == PATH_ITEMS_KIND {
+            // OpenAPI 3.0 has no components for path items, so it inlined
+            debug!("Inlined path item [{}]", reference);
+            self.bundle_value(&mut content, Node::Item(Kind::PathItem))?;
+            return Ok(BundledReference::Inline(content));
+        }
+
+        let name = self.get_unique_name(&kind, &get_component_name(&content, document, fragment));
+        debug!(
+            "Imported [{}] as [{}]",
+            absolute_uri,
+            get_local_reference(&kind, &name)
+        );
+        self.imported
+            .insert(absolute_uri.clone(), (kind.clone(), name.clone()));
+
+        self.bundle_value(&mut content, item)?;
+
+        self.components
+            .entry(kind.clone())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .expect("Imported components is not an object")
+            .insert(name.clone(), content);
+
+        Ok(BundledReference::Local(get_local_reference(&kind, &name)))
+    }
+
+    fn get_unique_name(&mut self, kind: &str, name: &str) -> String {
+        let mut unique_name = name.to_owned();
+        let mut index = 1;
+        while self
+            .taken_names
+            .contains(&(kind.to_owned(), unique_name.clone()))
+        {
+            unique_name = format!("{}{}", name, index);
+            index += 1;
+        }
+        self.taken_names
+            .insert((kind.to_owned(), unique_name.clone()));
+        unique_name
+    }
+}
+
+fn get_local_reference(kind: &str, name: &str) -> String {
+    format!("#/{}/{}/{}", COMPONENTS_KEY, kind, spec_keys::escape(name))
+}
+
+/// Name from x-ot-name extension, anchor, last pointer segment or document file name
+fn get_component_name(content: &Value, document: &str, fragment: &str) -> String {
+    let name = content
+        .get(EXTENSION_FOR_NAME)
+        .and_then(Value::as_str)
+        .map(String::from)
+        .or_else(|| {
+            // Anchor name
+            Some(fragment)
+                .filter(|it| !it.is_empty() && !it.starts_with('/'))
+                .map(String::from)
+        })
+        .or_else(|| {
+            references::parse_json_pointer(fragment)
+                .ok()
+                .and_then(|mut it| it.pop())
+                .filter(|it| !it.is_empty())
+        })
+        .unwrap_or_else(|| {
+            let file_name = document.rsplit('/').next().unwrap_or(document);
+            file_name
+                .split_once('.')
+                .map_or(file_name, |it| it.0)
+                .to_owned()
+        });
+
+    name.chars()
+        .map(|it| {
+            if it.is_ascii_alphanumeric() || it == '.' || it == '-' || it == '_' {
+                it
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Kind of component by structural position of reference, by pointer to target in components
+/// if position is unknown
+fn get_component_kind(fragment: &str, node: Node) -> (String, Node) {
+    let kind = match node {
+        Node::Item(item) => match item {
+            Kind::PathItem => Some(PATH_ITEMS_KIND),
+            Kind::Parameter => Some(PARAMETERS_KEY),
+            Kind::RequestBody => Some("requestBodies"),
+            Kind::Response => Some("responses"),
+            Kind::Header => Some("headers"),
+            Kind::Example => Some("examples"),
+            Kind::Link => Some("links"),
+            Kind::Callback => Some("callbacks"),
+            Kind::SecurityScheme => Some("securitySchemes"),
+            Kind::Schema => Some(SCHEMAS_KEY),
+            Kind::Operation | Kind::MediaType | Kind::Encoding => None,
+        },
+        _ => None,
+    };
+    if let Some(kind) = kind {
+        return (kind.to_owned(), node);
+    }
+
+    let segments: Vec<&str> = fragment.split('/').collect();
+    let kind = match segments.as_slice() {
+        ["", COMPONENTS_KEY, kind, _] => *kind,
+        _ => SCHEMAS_KEY,
+    };
+    (
+        kind.to_owned(),
+        get_child_node(Node::Components, kind).into_item(),
+    )
+}
+
+/// Position of child by position of its holder and key in it
+fn get_child_node(node: Node, key: &str) -> Node {
+    let schema = Node::Item(Kind::Schema);
+    match node {
+        Node::Spec => match key {
+            PATHS_KEY | WEBHOOKS_KEY => Node::Map(Kind::PathItem),
+            COMPONENTS_KEY => Node::Components,
+            _ => Node::Other,
+        },
+        Node::Components => match key {
+            SCHEMAS_KEY => Node::Map(Kind::Schema),
+            "responses" => Node::Map(Kind::Response),
+            PARAMETERS_KEY => Node::Map(Kind::Parameter),
+            "examples" => Node::Map(Kind::Example),
+            "requestBodies" => Node::Map(Kind::RequestBody),
+            "headers" => Node::Map(Kind::Header),
+            "securitySchemes" => Node::Map(Kind::SecurityScheme),
+            "links" => Node::Map(Kind::Link),
+            "callbacks" => Node::Map(Kind::Callback),
+            PATH_ITEMS_KIND => Node::Map(Kind::PathItem),
+            _ => Node::Other,
+        },
+        Node::Map(item) => Node::Item(item),
+        Node::List(_) | Node::Other => Node::Other,
+        Node::Item(item) => match (item, key) {
+            (Kind::PathItem | Kind::Operation, PARAMETERS_KEY) => Node::List(Kind::Parameter),
+            (Kind::PathItem, _) if OPERATION_KEYS.contains(&key) => Node::Item(Kind::Operation),
+            (Kind::Operation, "requestBody") => Node::Item(Kind::RequestBody),
+            (Kind::Operation, "responses") => Node::Map(Kind::Response),
+            (Kind::Operation, "callbacks") => Node::Map(Kind::Callback),
+            (Kind::Callback, _) => Node::Item(Kind::PathItem),
+            (Kind::Parameter | Kind::Header | Kind::MediaType, "schema") => schema,
+            (Kind::Parameter | Kind::Header | Kind::MediaType, "examples") => {
+                Node::Map(Kind::Example)
+            }
+            (Kind::Parameter | Kind::Header | Kind::RequestBody | Kind::Response, "content") => {
+                Node::Map(Kind::MediaType)
+            }
+            (Kind::MediaType, "encoding") => Node::Map(Kind::Encoding),
+            (Kind::Response | Kind::Encoding, "headers") => Node::Map(Kind::Header),
+            (Kind::Response, "links") => Node::Map(Kind::Link),
+            (Kind::Schema, _) if SUBSCHEMA_MAPS_KEYS.contains(&key) => Node::Map(Kind::Schema),
+            (Kind::Schema, _) if SUBSCHEMA_LISTS_KEYS.contains(&key) => Node::List(Kind::Schema),
+            (Kind::Schema, _) if SUBSCHEMA_KEYS.contains(&key) => schema,
+            _ => Node::Other,
+        },
+    }
+}
+
+impl Node {
+    /// Position of object in map of components
+    fn into_item(self) -> Node {
+        match self {
+            Node::Map(item) => Node::Item(item),
+            _ => Node::Other,
+        }
+    }
+}
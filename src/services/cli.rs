@@ -1,11 +1,11 @@
 use anyhow::{Context, Result};
+use indexmap::IndexMap;
 use log::error;
 use mlua::LuaSerdeExt;
-use openapiv3::OpenAPI;
-use std::ffi::OsStr;
+use std::{ffi::OsStr, sync::Arc};
 
 use crate::{
-    enums::common::Script,
+    enums::common::{InputKind, MergeConflict, Script},
     holders::context::{
         get_lua_vm, recreate_lua_vm, CLI, DEFAULT_TESTS_EXPECTED_DIR_NAME,
         DEFAULT_TESTS_OPENAPI_DIR_NAME, DEFAULT_TESTS_OPENAPI_FILE_NAME,
@@ -13,17 +13,23 @@ use crate::{
         NULL_VALUE_VARIABLE_NAME_IN_LUA, TARGET_PARAMETERS_VARIABLE_NAME_IN_LUA,
         TARGET_PATH_VARIABLE_NAME_IN_LUA, VISITORS_PATH_VARIABLE_NAME_IN_LUA,
     },
+    structs::common::{ParsedSpec, SpecIndex},
     Commands,
 };
 
-use super::visitors;
+use super::{bundle, dereference, indexing, lint, validation, visitors};
 
-pub fn set_global_lua_parameters(openapi: &OpenAPI) -> Result<()> {
+/// Spec index is set before target script, so every script can query it
+pub fn set_global_lua_parameters(
+    extensions: &IndexMap<String, serde_json::Value>,
+    spec_index: SpecIndex,
+    parsed_spec: Option<Arc<ParsedSpec>>,
+) -> Result<()> {
     recreate_lua_vm();
     let lua_vm = get_lua_vm();
+    indexing::set_lua_spec_index(&lua_vm, spec_index, parsed_spec)?;
 
-    openapi
-        .extensions
+    extensions
         .get(EXTENSION_TARGET_PARAMETERS_NAME)
         .map(|it| {
             let params_value = lua_vm.to_value(it)?;
@@ -47,9 +53,14 @@ pub fn set_global_lua_parameters(openapi: &OpenAPI) -> Result<()> {
     lua_vm
         .globals()
         .set(NULL_VALUE_VARIABLE_NAME_IN_LUA, fake_null)?;
+    Ok(())
+}
 
+/// Paths of visitors and target scripts are set to Lua and target script is run before translation
+pub fn load_target_scripts() -> Result<()> {
+    let lua_vm = get_lua_vm();
     let visitors_path_str = CLI
-        .get_visitors_dir()
+        .get_visitors_dir()?
         .to_str()
         .expect("unable to get string from visitors path");
     lua_vm.globals().set(
@@ -58,7 +69,7 @@ pub fn set_global_lua_parameters(openapi: &OpenAPI) -> Result<()> {
     )?;
 
     let target_path_str = CLI
-        .get_target_dir()
+        .get_target_dir()?
         .to_str()
         .expect("unable to get string from visitors path");
     lua_vm.globals().set(
@@ -93,11 +104,21 @@ pub fn visit_commands() -> Result<()> {
                 (*it).clone_into(&mut log_test_name);
             }
         })
-        .try_for_each(visitors::visit_command)?;
+        .try_for_each(|command| match command {
+            Commands::Bundle { .. } => bundle::bundle_command(command),
+            Commands::Dereference { .. } => dereference::dereference_command(command),
+            Commands::Validate { .. } => validation::validate_command(command),
+            Commands::Lint { .. } => lint::lint_command(command),
+            _ => visitors::visit_command(command),
+        })?;
     Ok(())
 }
 
 fn get_commands() -> Result<Vec<Commands>> {
+    if let Commands::Test { .. } | Commands::Translate { .. } = &CLI.command {
+        CLI.get_target_dir()?;
+        CLI.get_visitors_dir()?;
+    }
     let commands = match &CLI.command {
         Commands::Test { names, tests } => {
             let commands: Vec<Commands> = tests
@@ -114,12 +135,14 @@ fn get_commands() -> Result<Vec<Commands>> {
                         }))
                 })
                 .map(|test| Commands::Translate {
-                    spec: test
+                    spec: vec![test
                         .join(DEFAULT_TESTS_OPENAPI_DIR_NAME)
-                        .join(DEFAULT_TESTS_OPENAPI_FILE_NAME),
+                        .join(DEFAULT_TESTS_OPENAPI_FILE_NAME)],
                     out: test.join(DEFAULT_TESTS_OUT_DIR_NAME),
                     clean: true,
                     expected: Some(test.join(DEFAULT_TESTS_EXPECTED_DIR_NAME)),
+                    input_kind: InputKind::Openapi,
+                    merge_conflict: MergeConflict::Report,
                     test_name: test
                         .as_path()
                         .file_name()
@@ -138,14 +161,24 @@ fn get_commands() -> Result<Vec<Commands>> {
             out,
             clean,
             expected,
+            input_kind,
+            merge_conflict,
             test_name: _,
         } => vec![Commands::Translate {
-            spec: spec.to_owned(),
+            spec: spec.clone(),
             out: out.to_owned(),
             clean: *clean,
             expected: expected.clone(),
+            input_kind: *input_kind,
+            merge_conflict: *merge_conflict,
             test_name: None,
         }],
+        Commands::Bundle { .. }
+        | Commands::Dereference { .. }
+        | Commands::Validate { .. }
+        | Commands::Lint { .. } => {
+            vec![CLI.command.clone()]
+        }
     };
 
     Ok(commands)
@@ -4,7 +4,8 @@ use std::{
     path::Path,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
 use log::{debug, info, warn};
 
 use crate::{enums::common::WriteMode, structs::common::Code};
@@ -16,57 +17,145 @@ pub fn save_code(out_path: &Path, code: Vec<Code>) -> Result<()> {
         if let Some(parent) = code_path.parent() {
             fs::create_dir_all(parent).expect("Failed to create directories");
         }
-        modify_file(&code_path, &it.code, &it.mode).with_context(|| {
+        let content = get_content(it)
+            .with_context(|| format!("Could not get content for [{:?}]", &code_path))?;
+        let written = modify_file(&code_path, &content, &it.mode).with_context(|| {
             format!(
                 "Could not write code with mode [{:?}] to [{:?}]",
                 &it.mode, &code_path
             )
-        })
+        })?;
+        // Only files written by this operation get permissions, a kept user file stays untouched
+        if let Some(permissions) = it.permissions.as_ref().filter(|_| written) {
+            set_permissions(&code_path, permissions).with_context(|| {
+                format!(
+                    "Could not set permissions [{}] to [{:?}]",
+                    permissions, &code_path
+                )
+            })?;
+        }
+        Ok(())
     })
 }
 
-fn modify_file(file_path: &Path, text: &Option<String>, mode: &WriteMode) -> Result<()> {
+fn get_content(code: &Code) -> Result<Option<Vec<u8>>> {
+    match (&code.base64, &code.code) {
+        (Some(encoded), _) => Ok(Some(
+            STANDARD
+                .decode(encoded.trim())
+                .context("Could not decode base64 content")?,
+        )),
+        (None, Some(text)) => Ok(Some(text.as_bytes().to_vec())),
+        (None, None) => Ok(None),
+    }
+}
+
+#[cfg(unix)]
+fn set_permissions(file_path: &Path, permissions: &str) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if !file_path.exists() {
+        info!("File for permissions is not exists [{:?}]", file_path);
+        return Ok(());
+    }
+    let mode = u32::from_str_radix(permissions, 8)
+        .map_err(|_| anyhow!("Not a octal unix file mode [{}]", permissions))?;
+    fs::set_permissions(file_path, fs::Permissions::from_mode(mode))?;
+    debug!(
+        "Set permissions [{}] to file [{:?}]",
+        permissions, file_path
+    );
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_permissions(file_path: &Path, permissions: &str) -> Result<()> {
+    warn!(
+        "Unix file mode [{}] is not supported on this platform for [{:?}]",
+        permissions, file_path
+    );
+    Ok(())
+}
+
+/// Returns true if content was written to the file
+fn modify_file(file_path: &Path, content: &Option<Vec<u8>>, mode: &WriteMode) -> Result<bool> {
     let file_exists = File::open(file_path).is_ok();
 
-    match mode {
+    let written = match mode {
         WriteMode::Prepend => {
-            if let Some(text) = text {
+            if let Some(content) = content {
                 if file_exists {
                     let mut existing_file = OpenOptions::new().read(true).open(file_path)?;
 
-                    let mut contents = String::new();
-                    existing_file.read_to_string(&mut contents)?;
+                    let mut contents = Vec::new();
+                    existing_file.read_to_end(&mut contents)?;
 
                     let mut file = OpenOptions::new()
                         .write(true)
                         .truncate(true)
                         .open(file_path)?;
 
-                    file.write_all(text.as_bytes())
+                    file.write_all(content)
                         .inspect(|_| debug!("Prepend to file [{:?}]", file_path))
                         .with_context(|| format!("Prepend to file [{:?}] error", file_path))?;
-                    file.write_all(contents.as_bytes())
+                    file.write_all(&contents)
                         .with_context(|| format!("Prepend to file [{:?}] error", file_path))?;
                 } else {
                     let mut file = File::create(file_path)?;
-                    file.write_all(text.as_bytes())?;
+                    file.write_all(content)?;
                 }
+                true
             } else {
-                warn!("Empty text prepend to file [{:?}]", file_path)
+                warn!("Empty text prepend to file [{:?}]", file_path);
+                false
             }
         }
         WriteMode::Append => {
-            if let Some(text) = text {
+            if let Some(content) = content {
                 let mut file = OpenOptions::new()
                     .append(true)
                     .create(true)
                     .open(file_path)?;
 
-                file.write_all(text.as_bytes())
+                file.write_all(content)
                     .inspect(|_| debug!("Append to file [{:?}]", file_path))
                     .with_context(|| format!("Append to file [{:?}] error", file_path))?;
+                true
             } else {
-                warn!("Empty text prepend to file [{:?}]", file_path)
+                warn!("Empty text prepend to file [{:?}]", file_path);
+                false
+            }
+        }
+        WriteMode::Overwrite => {
+            if let Some(content) = content {
+                let mut file = File::create(file_path)?;
+
+                file.write_all(content)
+                    .inspect(|_| debug!("Overwrite file [{:?}]", file_path))
+                    .with_context(|| format!("Overwrite file [{:?}] error", file_path))?;
+                true
+            } else {
+                warn!("Empty text overwrite file [{:?}]", file_path);
+                false
+            }
+        }
+        WriteMode::CreateIfAbsent => {
+            if file_exists {
+                info!("File already exists, skip creation [{:?}]", file_path);
+                false
+            } else if let Some(content) = content {
+                let mut file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(file_path)?;
+
+                file.write_all(content)
+                    .inspect(|_| debug!("Create file [{:?}]", file_path))
+                    .with_context(|| format!("Create file [{:?}] error", file_path))?;
+                true
+            } else {
+                warn!("Empty text create file [{:?}]", file_path);
+                false
             }
         }
         WriteMode::Remove => {
@@ -78,8 +167,9 @@ fn modify_file(file_path: &Path, text: &Option<String>, mode: &WriteMode) -> Res
             } else {
                 info!("File for removing is not exists [{:?}]", file_path);
             }
+            false
         }
-    }
+    };
 
-    Ok(())
+    Ok(written)
 }
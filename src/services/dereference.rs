@@ -0,0 +1,115 @@
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+use serde_json::Value;
+
+use crate::{
+    services::{
+        bundle, references,
+        spec_keys::{self, COMPONENTS_KEY, REFERENCE_KEY},
+    },
+    structs::common::ParsedSpec,
+    Commands,
+};
+
+pub fn dereference_command(command: &Commands) -> Result<()> {
+    if let Commands::Dereference {
+        spec: spec_path,
+        out: out_path,
+    } = command
+    {
+        info!("Dereference start for [{:?}]", spec_path);
+
+        let parsed_spec = references::load_spec(spec_path)?;
+        // Targets of cycles must stay reachable by local refs, so external ones are pulled into components first
+        let bundled = bundle::bundle_spec(&parsed_spec)?;
+        let bundled_spec = ParsedSpec::new(spec_path.clone(), bundled)?;
+
+        let dereferenced = dereference_spec(&bundled_spec)?;
+        bundle::write_spec(out_path, &dereferenced)?;
+
+        info!("Dereference end, written to [{:?}]", out_path);
+        Ok(())
+    } else {
+        Err(anyhow!("Expected a Dereference command"))
+    }
+}
+
+/// Inlines every reference of spec, references closing a cycle are left local
+pub fn dereference_spec(parsed_spec: &ParsedSpec) -> Result<Value> {
+    let mut dereferenced = parsed_spec.spec.as_ref().clone();
+
+    // Component is a start of own chain, so reference back to it is a cycle
+    if let Some(Value::Object(components)) = dereferenced.get_mut(COMPONENTS_KEY) {
+        for (kind, named) in components.iter_mut() {
+            if let Value::Object(named) = named {
+                for (name, component) in named.iter_mut() {
+                    let reference =
+                        format!("#/{}/{}/{}", COMPONENTS_KEY, kind, spec_keys::escape(name));
+                    let mut chain = vec![references::absolute_reference(&reference, parsed_spec)?];
+                    dereference_value(parsed_spec, component, false, &mut chain)?;
+                }
+            }
+        }
+    }
+
+    if let Value::Object(map) = &mut dereferenced {
+        for (key, child) in map.iter_mut().filter(|(key, child)| {
+            *key != COMPONENTS_KEY && !spec_keys::is_literal(key, child, false)
+        }) {
+            debug!("Dereference [{}]", key);
+            let is_named_map = spec_keys::is_named_map(key, false);
+            dereference_value(parsed_spec, child, is_named_map, &mut Vec::new())?;
+        }
+    }
+    Ok(dereferenced)
+}
+
+/// References in examples, defaults and extensions are instance data, so they are kept as is
+fn dereference_value(
+    parsed_spec: &ParsedSpec,
+    value: &mut Value,
+    in_named_map: bool,
+    chain: &mut Vec<String>,
+) -> Result<()> {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get(REFERENCE_KEY).filter(|_| !in_named_map)
+            {
+                let absolute_uri = references::absolute_reference(reference, parsed_spec)?;
+                let (_, fragment) = references::split_reference(&absolute_uri);
+
+                if chain.contains(&absolute_uri) {
+                    debug!("Left cyclic reference [{}]", reference);
+                    map.insert(
+                        REFERENCE_KEY.to_owned(),
+                        Value::String(format!("#{}", fragment)),
+                    );
+                    return Ok(());
+                }
+
+                let mut content = references::resolve_value(&absolute_uri, parsed_spec)?;
+                chain.push(absolute_uri);
+                let dereferenced = dereference_value(parsed_spec, &mut content, false, chain);
+                chain.pop();
+                dereferenced?;
+
+                *value = content;
+            } else {
+                for (key, child) in map.iter_mut() {
+                    if spec_keys::is_literal(key, child, in_named_map) {
+                        continue;
+                    }
+                    let is_named_map = spec_keys::is_named_map(key, in_named_map);
+                    dereference_value(parsed_spec, child, is_named_map, chain)?;
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items.iter_mut() {
+                dereference_value(parsed_spec, item, false, chain)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
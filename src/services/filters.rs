@@ -0,0 +1,252 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Context, Result};
+use glob::Pattern;
+use indexmap::IndexMap;
+use log::info;
+use serde_json::Value;
+
+use crate::{
+    holders::context::{CLI, EXTENSION_FILTERS_NAME},
+    services::{
+        references,
+        spec_keys::{
+            self, child_location, COMPONENTS_KEY, OPERATION_KEYS, PARAMETERS_KEY, PATHS_KEY,
+            REFERENCE_KEY, SCHEMAS_KEY, WEBHOOKS_KEY,
+        },
+    },
+    structs::common::{Filters, ParsedSpec, Selection},
+};
+
+/// Filters of spec extension, every filter set by CLI replaces the same one of spec
+pub fn get_filters(extensions: &IndexMap<String, Value>) -> Result<Filters> {
+    let mut filters: Filters = extensions
+        .get(EXTENSION_FILTERS_NAME)
+        .map(|it| serde_json::from_value(it.clone()))
+        .transpose()
+        .with_context(|| format!("Invalid filters in [{}]", EXTENSION_FILTERS_NAME))?
+        .unwrap_or_default();
+
+    let replace = |filter: &mut Vec<String>, values: &Vec<String>| {
+        if !values.is_empty() {
+            filter.clone_from(values);
+        }
+    };
+    replace(&mut filters.include_tags, &CLI.include_tags);
+    replace(&mut filters.exclude_tags, &CLI.exclude_tags);
+    replace(&mut filters.include_paths, &CLI.include_paths);
+    replace(&mut filters.include_operations, &CLI.include_operations);
+    replace(&mut filters.include_schemas, &CLI.include_schemas);
+    filters.prune_schemas |= CLI.prune_schemas;
+    Ok(filters)
+}
+
+/// Path items and operations under paths and webhooks are checked by filters, path item is excluded
+/// if none of its operations is included. Callbacks are not filtered.
+pub fn select(parsed_spec: &ParsedSpec, filters: &Filters) -> Result<Option<Selection>> {
+    if is_empty(filters) {
+        return Ok(None);
+    }
+    let path_patterns = filters
+        .include_paths
+        .iter()
+        .map(|it| Pattern::new(it).with_context(|| format!("Invalid path glob pattern [{}]", it)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let root_uri = references::absolute_reference("", parsed_spec)?;
+    let mut considered = HashSet::new();
+    let mut included = HashSet::new();
+    // Included operations and parameters of their path items, schemas are reachable from them
+    let mut roots = Vec::new();
+
+    for key in [PATHS_KEY, WEBHOOKS_KEY] {
+        let Some(Value::Object(items)) = parsed_spec.spec.get(key) else {
+            continue;
+        };
+        for (name, item) in items {
+            let item_location = child_location(&child_location(&root_uri, key), name);
+            let (item, target_location) = resolve_located(parsed_spec, item, &item_location)?;
+            considered.insert(item_location.clone());
+            considered.insert(target_location.clone());
+            if key == PATHS_KEY
+                && !path_patterns.is_empty()
+                && !path_patterns.iter().any(|it| it.matches(name))
+            {
+                continue;
+            }
+
+            for method in OPERATION_KEYS {
+                let Some(operation) = item.get(method) else {
+                    continue;
+                };
+                let operation_location = child_location(&target_location, method);
+                considered.insert(operation_location.clone());
+                if is_operation_included(filters, operation) {
+                    included.insert(operation_location);
+                    included.insert(item_location.clone());
+                    included.insert(target_location.clone());
+                    roots.push(operation.clone());
+                    if let Some(parameters) = item.get(PARAMETERS_KEY) {
+                        roots.push(parameters.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    let schemas = if filters.prune_schemas {
+        let mut visited = HashSet::new();
+        for name in &filters.include_schemas {
+            let reference = spec_keys::get_schema_reference(name);
+            collect_reference(parsed_spec, &reference, &mut visited)?;
+        }
+        for root in &roots {
+            collect_references(parsed_spec, root, &mut visited)?;
+        }
+        Some(get_schema_names(&root_uri, &visited))
+    } else if !filters.include_schemas.is_empty() {
+        Some(filters.include_schemas.iter().cloned().collect())
+    } else {
+        None
+    };
+
+    let excluded_locations: HashSet<String> = considered.difference(&included).cloned().collect();
+    info!(
+        "Filters exclude [{}] path items and operations, [{}] schemas are selected",
+        excluded_locations.len(),
+        schemas
+            .as_ref()
+            .map(|it| it.len().to_string())
+            .unwrap_or_else(|| "all".to_owned())
+    );
+    Ok(Some(Selection {
+        excluded_locations,
+        schemas,
+    }))
+}
+
+/// Node visited at the moment is excluded by filters
+pub fn is_excluded(parsed_spec: &ParsedSpec) -> bool {
+    parsed_spec.selection.as_ref().is_some_and(|selection| {
+        parsed_spec
+            .get_visited_location()
+            .is_some_and(|it| selection.excluded_locations.contains(&it))
+    })
+}
+
+pub fn is_schema_included(parsed_spec: &ParsedSpec, schema_name: &str) -> bool {
+    parsed_spec
+        .selection
+        .as_ref()
+        .and_then(|it| it.schemas.as_ref())
+        .is_none_or(|it| it.contains(schema_name))
+}
+
+fn is_empty(filters: &Filters) -> bool {
+    filters.include_tags.is_empty()
+        && filters.exclude_tags.is_empty()
+        && filters.include_paths.is_empty()
+        && filters.include_operations.is_empty()
+        && filters.include_schemas.is_empty()
+        && !filters.prune_schemas
+}
+
+fn is_operation_included(filters: &Filters, operation: &Value) -> bool {
+    let tags: Vec<&str> = operation
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(|it| it.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let operation_id = operation.get("operationId").and_then(Value::as_str);
+
+    (filters.include_tags.is_empty()
+        || tags
+            .iter()
+            .any(|it| filters.include_tags.iter().any(|tag| tag == it)))
+        && !tags
+            .iter()
+            .any(|it| filters.exclude_tags.iter().any(|tag| tag == it))
+        && (filters.include_operations.is_empty()
+            || operation_id.is_some_and(|id| filters.include_operations.iter().any(|it| it == id)))
+}
+
+/// Node itself or final target of its reference, with location where it is visited
+fn resolve_located(
+    parsed_spec: &ParsedSpec,
+    value: &Value,
+    location: &str,
+) -> Result<(Value, String)> {
+    match value.get(REFERENCE_KEY).and_then(Value::as_str) {
+        Some(reference) => {
+            let chain = references::resolve_reference_chain(reference, parsed_spec)?;
+            let target_uri = chain
+                .last()
+                .ok_or_else(|| anyhow!("Reference [{}] is not resolved", reference))?;
+            Ok((
+                references::resolve_value(target_uri, parsed_spec)?,
+                target_uri.clone(),
+            ))
+        }
+        None => Ok((value.clone(), location.to_owned())),
+    }
+}
+
+/// Targets of references are walked once, discriminator mappings are followed as references too
+fn collect_references(
+    parsed_spec: &ParsedSpec,
+    value: &Value,
+    visited: &mut HashSet<String>,
+) -> Result<()> {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get(REFERENCE_KEY) {
+                collect_reference(parsed_spec, reference, visited)?;
+            }
+            if let Some(Value::Object(mapping)) =
+                map.get("discriminator").and_then(|it| it.get("mapping"))
+            {
+                for target in mapping.values().filter_map(Value::as_str) {
+                    let reference = references::get_discriminator_reference(target, parsed_spec);
+                    collect_reference(parsed_spec, &reference, visited)?;
+                }
+            }
+            map.values()
+                .try_for_each(|it| collect_references(parsed_spec, it, visited))
+        }
+        Value::Array(items) => items
+            .iter()
+            .try_for_each(|it| collect_references(parsed_spec, it, visited)),
+        _ => Ok(()),
+    }
+}
+
+fn collect_reference(
+    parsed_spec: &ParsedSpec,
+    reference: &str,
+    visited: &mut HashSet<String>,
+) -> Result<()> {
+    let target_uri = references::absolute_reference(reference, parsed_spec)?;
+    if !visited.insert(target_uri.clone()) {
+        return Ok(());
+    }
+    let target = references::resolve_value(&target_uri, parsed_spec)
+        .with_context(|| format!("Could not resolve [{}] while schemas are pruned", reference))?;
+    collect_references(parsed_spec, &target, visited)
+}
+
+/// Names of root spec component schemas, targets inside of schema select whole schema
+fn get_schema_names(root_uri: &str, visited: &HashSet<String>) -> HashSet<String> {
+    let (root_document, _) = references::split_reference(root_uri);
+    let schemas_pointer = format!("/{}/{}/", COMPONENTS_KEY, SCHEMAS_KEY);
+    visited
+        .iter()
+        .filter_map(|it| {
+            let (document, fragment) = references::split_reference(it);
+            if document != root_document {
+                return None;
+            }
+            let name = fragment.strip_prefix(&schemas_pointer)?.split('/').next()?;
+            Some(spec_keys::unescape(name))
+        })
+        .collect()
+}
@@ -0,0 +1,265 @@
+use std::{fs, io::Read, thread, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use reqwest::{
+    blocking::{Client, Response},
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Certificate, Proxy, StatusCode,
+};
+use url::Url;
+
+use crate::{
+    holders::context::{CLI, CLIENT},
+    Cli,
+};
+
+const BODY_SNIPPET_LENGTH: usize = 200;
+
+pub fn fetch_url_content(url: &Url) -> Result<Box<dyn Read>> {
+    debug!("Resolved external $ref [{}]", url);
+    let client = CLIENT.get_or_try_init(|| build_client(&CLI))?;
+    fetch_with_retries(&CLI, client, url)
+}
+
+/// Request is retried on connection error, timeout, 429 or 5xx status with doubled delay
+fn fetch_with_retries(cli: &Cli, client: &Client, url: &Url) -> Result<Box<dyn Read>> {
+    let headers = get_host_headers(cli, url)?;
+
+    let mut attempt = 0;
+    loop {
+        let failure = match client.get(url.clone()).headers(headers.clone()).send() {
+            Ok(response) if response.status().is_success() => return Ok(Box::new(response)),
+            Ok(response) if is_retryable_status(response.status()) => {
+                get_status_error(url, response)
+            }
+            Ok(response) => return Err(get_status_error(url, response)),
+            Err(error) if error.is_connect() || error.is_timeout() => {
+                anyhow!(error).context(format!("Remote request to [{}] error", url))
+            }
+            Err(error) => {
+                return Err(anyhow!(error).context(format!("Remote request to [{}] error", url)))
+            }
+        };
+
+        if attempt >= cli.http_retries {
+            return Err(failure);
+        }
+        let delay =
+            Duration::from_millis(cli.http_retry_backoff.saturating_mul(1 << attempt.min(16)));
+        attempt += 1;
+        warn!(
+            "Retry [{}/{}] of [{}] in [{:?}] after error: {:#}",
+            attempt, cli.http_retries, url, delay, failure
+        );
+        thread::sleep(delay);
+    }
+}
+
+fn build_client(cli: &Cli) -> Result<Client> {
+    let mut builder = Client::builder().timeout(Duration::from_secs(cli.http_timeout));
+
+    if let Some(path) = &cli.http_ca_cert_path {
+        let pem = fs::read(path)
+            .with_context(|| format!("Could not read CA certificates [{:?}]", path))?;
+        for certificate in Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("Could not parse CA certificates [{:?}]", path))?
+        {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    if let Some(proxy) = &cli.http_proxy {
+        builder =
+            builder.proxy(Proxy::all(proxy).with_context(|| format!("Invalid proxy [{}]", proxy))?);
+    }
+
+    builder.build().context("Could not create HTTP client")
+}
+
+/// Headers configured for host or for host with port of url
+fn get_host_headers(cli: &Cli, url: &Url) -> Result<HeaderMap> {
+    let host = url.host_str().unwrap_or_default();
+    let host_with_port = url.port().map(|port| format!("{}:{}", host, port));
+
+    let mut headers = HeaderMap::new();
+    for header in cli
+        .http_headers
+        .iter()
+        .filter(|it| it.host == host || Some(&it.host) == host_with_port.as_ref())
+    {
+        let value = std::env::var(&header.env_var).with_context(|| {
+            format!(
+                "Environment variable [{}] for header [{}] of host [{}] is not set",
+                header.env_var, header.name, header.host
+            )
+        })?;
+        headers.insert(
+            HeaderName::from_bytes(header.name.as_bytes())
+                .with_context(|| format!("Invalid header name [{}]", header.name))?,
+            HeaderValue::from_str(&value).with_context(|| {
+                format!(
+                    "Invalid value of header [{}] from [{}]",
+                    header.name, header.env_var
+                )
+            })?,
+        );
+        debug!("Added header [{}] for [{}]", header.name, url);
+    }
+    Ok(headers)
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+fn get_status_error(url: &Url, response: Response) -> anyhow::Error {
+    let status = response.status();
+    let body = response.text().unwrap_or_default();
+    let snippet: String = body.chars().take(BODY_SNIPPET_LENGTH).collect();
+    anyhow!(
+        "Failed to fetch the content by uri [{}], status [{}], body [{}{}]",
+        url,
+        status,
+        snippet,
+        if body.chars().count() > BODY_SNIPPET_LENGTH {
+            "..."
+        } else {
+            ""
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::{TcpListener, TcpStream},
+        sync::mpsc::{self, Receiver},
+    };
+
+    use clap::Parser;
+
+    use super::*;
+
+    /// Local server answers connections in order by given statuses and bodies and sends requests heads back
+    fn serve(responses: Vec<(u16, String)>) -> (Url, Receiver<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Could not bind local server");
+        let url = Url::parse(&format!(
+            "http://{}/spec.yml",
+            listener.local_addr().expect("Local server has no address")
+        ))
+        .expect("Invalid local server url");
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().expect("Could not accept connection");
+                sender.send(respond(stream, status, &body)).ok();
+            }
+        });
+        (url, receiver)
+    }
+
+    fn respond(mut stream: TcpStream, status: u16, body: &str) -> Vec<String> {
+        let head: Vec<String> = BufReader::new(&stream)
+            .lines()
+            .map_while(|it| it.ok())
+            .take_while(|it| !it.is_empty())
+            .collect();
+        write!(
+            stream,
+            "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+        .expect("Could not write response");
+        head
+    }
+
+    fn parse_cli(options: &[&str]) -> Cli {
+        let args = ["openapi-translator", "--http-retry-backoff", "1"]
+            .iter()
+            .chain(options)
+            .chain(&["validate", "--spec", "openapi.yml"]);
+        Cli::try_parse_from(args).expect("Invalid test options")
+    }
+
+    fn fetch(cli: &Cli, url: &Url) -> Result<String> {
+        let client = build_client(cli)?;
+        let mut content = String::new();
+        fetch_with_retries(cli, &client, url)?.read_to_string(&mut content)?;
+        Ok(content)
+    }
+
+    #[test]
+    fn sends_headers_of_request_host() {
+        std::env::set_var("OT_HTTP_TEST_TOKEN", "Bearer secret");
+        let (url, requests) = serve(vec![(200, "openapi: 3.0.3".to_owned())]);
+        let host = format!("{}:{}", url.host_str().unwrap(), url.port().unwrap());
+        let cli = parse_cli(&[
+            "--http-header",
+            &format!("{}=Authorization:OT_HTTP_TEST_TOKEN", host),
+            "--http-header",
+            "other.example.com=X-Other:OT_HTTP_TEST_TOKEN",
+        ]);
+
+        assert_eq!(fetch(&cli, &url).unwrap(), "openapi: 3.0.3");
+        let head = requests.recv().unwrap();
+        assert!(head.contains(&"authorization: Bearer secret".to_owned()));
+        assert!(!head.iter().any(|it| it.starts_with("x-other")));
+    }
+
+    #[test]
+    fn retries_server_errors_and_too_many_requests() {
+        let (url, requests) = serve(vec![
+            (503, String::new()),
+            (429, String::new()),
+            (200, "openapi: 3.0.3".to_owned()),
+        ]);
+        let cli = parse_cli(&["--http-retries", "2"]);
+
+        assert_eq!(fetch(&cli, &url).unwrap(), "openapi: 3.0.3");
+        assert_eq!(requests.iter().count(), 3);
+    }
+
+    #[test]
+    fn stops_after_retries_limit() {
+        let (url, requests) = serve(vec![
+            (500, "first".to_owned()),
+            (502, "second".to_owned()),
+            (200, "openapi: 3.0.3".to_owned()),
+        ]);
+        let cli = parse_cli(&["--http-retries", "1"]);
+
+        let error = fetch(&cli, &url).unwrap_err().to_string();
+        assert!(error.contains("status [502 Bad Gateway]"), "{}", error);
+        assert_eq!(requests.iter().take(2).count(), 2);
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        // Retry would fail by connection error, server closes after the only response
+        let (url, _requests) = serve(vec![(404, "missing".to_owned())]);
+        let cli = parse_cli(&["--http-retries", "3"]);
+
+        let error = fetch(&cli, &url).unwrap_err().to_string();
+        assert!(error.contains("status [404 Not Found]"), "{}", error);
+    }
+
+    #[test]
+    fn reports_status_and_body_snippet() {
+        let body = format!("{}{}", "e".repeat(BODY_SNIPPET_LENGTH), "tail");
+        let (url, _requests) = serve(vec![(403, body)]);
+        let cli = parse_cli(&[]);
+
+        let error = fetch(&cli, &url).unwrap_err().to_string();
+        assert!(error.contains("status [403 Forbidden]"), "{}", error);
+        assert!(
+            error.contains(&format!("body [{}...]", "e".repeat(BODY_SNIPPET_LENGTH))),
+            "{}",
+            error
+        );
+        assert!(!error.contains("tail"), "{}", error);
+    }
+}
@@ -0,0 +1,466 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use mlua::{Lua, LuaSerdeExt, Table};
+use serde_json::Value;
+
+use crate::{
+    holders::context::SPEC_VARIABLE_NAME_IN_LUA,
+    services::{
+        references,
+        spec_keys::{
+            self, ALL_OF_KEY, COMPONENTS_KEY, OPERATION_KEYS, PARAMETERS_KEY, PATHS_KEY,
+            REFERENCE_KEY, SCHEMAS_KEY, SUBSCHEMA_KEYS, SUBSCHEMA_LISTS_KEYS, SUBSCHEMA_MAPS_KEYS,
+            WEBHOOKS_KEY,
+        },
+    },
+    structs::common::{IndexedOperation, IndexedSchema, ParsedSpec, SpecIndex},
+};
+
+/// Schema or operation which references schemas found in it
+#[derive(Clone)]
+enum Owner {
+    Schema(String),
+    Operation(String),
+    Spec,
+}
+
+struct Indexer<'a> {
+    parsed_spec: &'a ParsedSpec,
+    root_document: String,
+    index: SpecIndex,
+    /// References to other than schemas followed by owner, such reference can be shared
+    followed: HashSet<(String, String)>,
+}
+
+/// Component schemas are indexed first to keep their names, then operations of paths and webhooks,
+/// then the rest of components for schemas used only there
+pub fn build_index(parsed_spec: &ParsedSpec) -> Result<SpecIndex> {
+    let mut indexer = Indexer::new(parsed_spec, true)?;
+    let spec = &parsed_spec.spec;
+
+    if let Some(Value::Object(schemas)) =
+        spec.get(COMPONENTS_KEY).and_then(|it| it.get(SCHEMAS_KEY))
+    {
+        for name in schemas.keys() {
+            indexer.index_schema(&spec_keys::get_schema_reference(name), Some(name))?;
+        }
+    }
+
+    for key in [PATHS_KEY, WEBHOOKS_KEY] {
+        if let Some(Value::Object(items)) = spec.get(key) {
+            for (path, item) in items {
+                indexer
+                    .index_path_item(path, item, key == WEBHOOKS_KEY)
+                    .with_context(|| format!("Could not index [{}] of [{}]", path, key))?;
+            }
+        }
+    }
+
+    if let Some(Value::Object(components)) = spec.get(COMPONENTS_KEY) {
+        for (kind, named) in components {
+            if kind != SCHEMAS_KEY && !kind.starts_with("x-") {
+                indexer.walk(named, false, true, &Owner::Spec)?;
+            }
+        }
+    }
+
+    Ok(indexer.finish())
+}
+
+/// Index of bare JSON Schema documents, root schema of every document is named by its file
+pub fn build_schemas_index(parsed_schemas: &[(&str, &ParsedSpec)]) -> Result<SpecIndex> {
+    let mut index = SpecIndex::default();
+    for (name, parsed_spec) in parsed_schemas {
+        // Root documents differ, so their schemas are keyed by absolute references
+        let mut indexer = Indexer::new(parsed_spec, false)?;
+        indexer.index_schema("#", Some(name))?;
+        index.schemas.extend(indexer.finish().schemas);
+    }
+    Ok(index)
+}
+
+/// Index data with functions to find schema by reference, operation by key and operations by tag,
+/// absent ones are nil. References are resolved against root spec, absolute references are found
+/// without it.
+pub fn set_lua_spec_index(
+    lua_vm: &Lua,
+    spec_index: SpecIndex,
+    parsed_spec: Option<Arc<ParsedSpec>>,
+) -> Result<()> {
+    let mlua::Value::Table(spec) = lua_vm.to_value(&spec_index)? else {
+        return Err(anyhow!("Spec index is not converted to Lua table"));
+    };
+    let spec_index = Arc::new(spec_index);
+    if let Some(parsed_spec) = parsed_spec.as_ref() {
+        set_lua_spec_access(lua_vm, &spec, parsed_spec)?;
+    }
+
+    let index = Arc::clone(&spec_index);
+    let base = parsed_spec.map(|it| it.uri.clone());
+    spec.set(
+        "getSchema",
+        lua_vm.create_function(move |lua, reference: String| {
+            let Some(base) = base.as_ref() else {
+                return index
+                    .schemas
+                    .get(&reference)
+                    .map(|it| lua.to_value(it))
+                    .transpose();
+            };
+            let absolute =
+                references::resolve_against(base, &reference).map_err(mlua::Error::external)?;
+            let (root_document, _) = references::split_reference(base.as_str());
+            index
+                .schemas
+                .get(&get_index_key(&absolute, root_document))
+                .or_else(|| index.schemas.get(&absolute))
+                .map(|it| lua.to_value(it))
+                .transpose()
+        })?,
+    )?;
+
+    let index = Arc::clone(&spec_index);
+    spec.set(
+        "getOperation",
+        lua_vm.create_function(move |lua, key: String| {
+            index
+                .operations
+                .get(&key)
+                .map(|it| lua.to_value(it))
+                .transpose()
+        })?,
+    )?;
+
+    let index = Arc::clone(&spec_index);
+    spec.set(
+        "getOperationsByTag",
+        lua_vm.create_function(move |lua, tag: String| {
+            let operations: Vec<&IndexedOperation> = index
+                .tags
+                .get(&tag)
+                .map(|keys| {
+                    keys.iter()
+                        .filter_map(|it| index.operations.get(it))
+                        .collect()
+                })
+                .unwrap_or_default();
+            lua.to_value(&operations)
+        })?,
+    )?;
+
+    lua_vm.globals().set(SPEC_VARIABLE_NAME_IN_LUA, spec)?;
+    Ok(())
+}
+
+/// Raw spec and targets of references are taken through the same documents cache and remote fetch
+/// as in translation, values are read only copies
+fn set_lua_spec_access(lua_vm: &Lua, spec: &Table, parsed_spec: &Arc<ParsedSpec>) -> Result<()> {
+    let root = Arc::clone(parsed_spec);
+    spec.set(
+        "raw",
+        lua_vm.create_function(move |lua, ()| lua.to_value(root.spec.as_ref()))?,
+    )?;
+
+    // Pointer is relative to root spec, `#` prefix is optional, absent node is nil
+    let root = Arc::clone(parsed_spec);
+    spec.set(
+        "get",
+        lua_vm.create_function(move |lua, pointer: String| {
+            let pointer = references::parse_json_pointer(pointer.trim_start_matches('#'))
+                .map_err(mlua::Error::external)?;
+            pointer
+                .get(root.spec.as_ref())
+                .ok()
+                .map(|it| lua.to_value(it))
+                .transpose()
+        })?,
+    )?;
+
+    // Chain of references is followed to the final target, its absolute reference is second result
+    let root = Arc::clone(parsed_spec);
+    spec.set(
+        "resolve",
+        lua_vm.create_function(move |lua, reference: String| {
+            let resolved = references::resolve_reference_chain(&reference, &root)
+                .and_then(|chain| {
+                    let target_uri = chain
+                        .last()
+                        .ok_or_else(|| anyhow!("Reference [{}] is not resolved", reference))?;
+                    Ok((
+                        references::resolve_value(target_uri, &root)?,
+                        target_uri.clone(),
+                    ))
+                })
+                .map_err(|error| mlua::Error::external(format!("{:#}", error)))?;
+            Ok((lua.to_value(&resolved.0)?, resolved.1))
+        })?,
+    )?;
+    Ok(())
+}
+
+impl<'a> Indexer<'a> {
+    /// Schemas of root spec are keyed by local references if it is set
+    fn new(parsed_spec: &'a ParsedSpec, local_keys: bool) -> Result<Self> {
+        let root_uri = references::absolute_reference("", parsed_spec)?;
+        let (root_document, _) = references::split_reference(&root_uri);
+        Ok(Indexer {
+            parsed_spec,
+            root_document: if local_keys {
+                root_document.to_owned()
+            } else {
+                String::new()
+            },
+            index: SpecIndex::default(),
+            followed: HashSet::new(),
+        })
+    }
+
+    /// Children are known only when every schema is indexed
+    fn finish(mut self) -> SpecIndex {
+        let inheritance: Vec<(String, String)> = self
+            .index
+            .schemas
+            .iter()
+            .flat_map(|(key, it)| {
+                it.parents
+                    .iter()
+                    .map(move |parent| (parent.clone(), key.clone()))
+            })
+            .collect();
+        for (parent, child) in inheritance {
+            if let Some(parent) = self.index.schemas.get_mut(&parent) {
+                push_unique(&mut parent.children, child);
+            }
+        }
+        info!(
+            "Spec index has [{}] schemas and [{}] operations",
+            self.index.schemas.len(),
+            self.index.operations.len()
+        );
+        self.index
+    }
+
+    fn index_path_item(&mut self, path: &str, item: &Value, webhook: bool) -> Result<()> {
+        let item = match item.get(REFERENCE_KEY).and_then(Value::as_str) {
+            Some(reference) => {
+                let chain = references::resolve_reference_chain(reference, self.parsed_spec)?;
+                match chain.last() {
+                    Some(target) => references::resolve_value(target, self.parsed_spec)?,
+                    None => return Ok(()),
+                }
+            }
+            None => item.clone(),
+        };
+
+        for method in OPERATION_KEYS {
+            let Some(operation) = item.get(method) else {
+                continue;
+            };
+            let operation_id = operation
+                .get("operationId")
+                .and_then(Value::as_str)
+                .map(str::to_owned);
+            let key = operation_id
+                .clone()
+                .unwrap_or_else(|| format!("{} {}", method.to_uppercase(), path));
+            let tags: Vec<String> = operation
+                .get("tags")
+                .and_then(Value::as_array)
+                .map(|it| {
+                    it.iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default();
+            for tag in &tags {
+                push_unique(self.index.tags.entry(tag.clone()).or_default(), key.clone());
+            }
+            self.index.operations.insert(
+                key.clone(),
+                IndexedOperation {
+                    operation_id,
+                    method: method.to_owned(),
+                    path: path.to_owned(),
+                    webhook,
+                    tags,
+                    operation: operation.clone(),
+                    uses: Vec::new(),
+                },
+            );
+
+            let owner = Owner::Operation(key);
+            self.walk(operation, false, false, &owner)?;
+            if let Some(parameters) = item.get(PARAMETERS_KEY) {
+                self.walk(parameters, false, false, &owner)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn index_schema(&mut self, reference: &str, name: Option<&str>) -> Result<String> {
+        let absolute = references::absolute_reference(reference, self.parsed_spec)?;
+        let key = get_index_key(&absolute, &self.root_document);
+        if let Some(indexed) = self.index.schemas.get_mut(&key) {
+            // Component referenced before its own entry is named when the entry is reached
+            if let Some(name) = name {
+                indexed.name.get_or_insert_with(|| name.to_owned());
+            }
+            return Ok(key);
+        }
+        let schema = references::resolve_value(&absolute, self.parsed_spec)
+            .with_context(|| format!("Could not index schema [{}]", reference))?;
+
+        let mut parents = Vec::new();
+        if let Some(Value::Array(all_of)) = schema.get(ALL_OF_KEY) {
+            for parent in all_of {
+                if let Some(parent) = parent.get(REFERENCE_KEY).and_then(Value::as_str) {
+                    let parent = references::absolute_reference(parent, self.parsed_spec)?;
+                    push_unique(&mut parents, get_index_key(&parent, &self.root_document));
+                }
+            }
+        }
+        self.index.schemas.insert(
+            key.clone(),
+            IndexedSchema {
+                reference: key.clone(),
+                name: name.map(str::to_owned),
+                schema: schema.clone(),
+                parents,
+                children: Vec::new(),
+                uses: Vec::new(),
+                used_by_schemas: Vec::new(),
+                used_by_operations: Vec::new(),
+            },
+        );
+
+        self.walk(&schema, true, false, &Owner::Schema(key.clone()))?;
+        Ok(key)
+    }
+
+    /// Schema references are indexed as schemas, other references are followed for schemas in their targets.
+    /// Instance data is skipped, but its keys are usual names in named maps, like `default` of responses
+    fn walk(
+        &mut self,
+        value: &Value,
+        is_schema: bool,
+        in_named_map: bool,
+        owner: &Owner,
+    ) -> Result<()> {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get(REFERENCE_KEY) {
+                    if is_schema {
+                        let used = self.index_schema(reference, None)?;
+                        self.add_usage(owner, used);
+                    } else {
+                        self.follow(reference, owner)?;
+                    }
+                }
+
+                for (key, child) in map {
+                    let key = key.as_str();
+                    match (is_schema, child) {
+                        (false, child) if spec_keys::is_literal(key, child, in_named_map) => {}
+                        (false, child) if key == "schema" && !in_named_map => {
+                            self.walk(child, true, false, owner)?
+                        }
+                        (false, child) => self.walk(
+                            child,
+                            false,
+                            spec_keys::is_named_map(key, in_named_map),
+                            owner,
+                        )?,
+                        (true, child) if SUBSCHEMA_KEYS.contains(&key) => {
+                            self.walk(child, true, false, owner)?
+                        }
+                        (true, Value::Array(items)) if SUBSCHEMA_LISTS_KEYS.contains(&key) => {
+                            for item in items {
+                                self.walk(item, true, false, owner)?;
+                            }
+                        }
+                        (true, Value::Object(named)) if SUBSCHEMA_MAPS_KEYS.contains(&key) => {
+                            for item in named.values() {
+                                self.walk(item, true, false, owner)?;
+                            }
+                        }
+                        (true, child) if key == "discriminator" => {
+                            self.walk_discriminator(child, owner)?
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(())
+            }
+            Value::Array(items) => items
+                .iter()
+                .try_for_each(|it| self.walk(it, is_schema, false, owner)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Mapping value is a reference if it looks like one, otherwise a name of schema in components
+    fn walk_discriminator(&mut self, discriminator: &Value, owner: &Owner) -> Result<()> {
+        let Some(Value::Object(mapping)) = discriminator.get("mapping") else {
+            return Ok(());
+        };
+        for target in mapping.values().filter_map(Value::as_str) {
+            let reference = references::get_discriminator_reference(target, self.parsed_spec);
+            let used = self.index_schema(&reference, None)?;
+            self.add_usage(owner, used);
+        }
+        Ok(())
+    }
+
+    fn follow(&mut self, reference: &str, owner: &Owner) -> Result<()> {
+        let target_uri = references::absolute_reference(reference, self.parsed_spec)?;
+        let owner_key = match owner {
+            Owner::Schema(key) | Owner::Operation(key) => key.clone(),
+            Owner::Spec => String::new(),
+        };
+        if !self.followed.insert((owner_key, target_uri.clone())) {
+            return Ok(());
+        }
+        let target = references::resolve_value(&target_uri, self.parsed_spec)
+            .with_context(|| format!("Could not index target of [{}]", reference))?;
+        self.walk(&target, false, false, owner)
+    }
+
+    fn add_usage(&mut self, owner: &Owner, used: String) {
+        match owner {
+            Owner::Schema(key) => {
+                if let Some(it) = self.index.schemas.get_mut(key) {
+                    push_unique(&mut it.uses, used.clone());
+                }
+                if let Some(it) = self.index.schemas.get_mut(&used) {
+                    push_unique(&mut it.used_by_schemas, key.clone());
+                }
+            }
+            Owner::Operation(key) => {
+                if let Some(it) = self.index.operations.get_mut(key) {
+                    push_unique(&mut it.uses, used.clone());
+                }
+                if let Some(it) = self.index.schemas.get_mut(&used) {
+                    push_unique(&mut it.used_by_operations, key.clone());
+                }
+            }
+            Owner::Spec => {}
+        }
+    }
+}
+
+/// References to root spec are local, so they match references of root spec passed to visitors
+fn get_index_key(absolute: &str, root_document: &str) -> String {
+    match references::split_reference(absolute) {
+        (document, fragment) if document == root_document => format!("#{}", fragment),
+        _ => absolute.to_owned(),
+    }
+}
+
+fn push_unique(items: &mut Vec<String>, item: String) {
+    if !items.contains(&item) {
+        items.push(item);
+    }
+}
@@ -0,0 +1,199 @@
+use log::{debug, warn};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    enums::common::InputKind,
+    holders::context::EXTENSION_FOR_JSON_SCHEMA_KEYWORDS,
+    services::spec_keys::{
+        self, SCHEMAS_KEY, SUBSCHEMA_KEYS, SUBSCHEMA_LISTS_KEYS, SUBSCHEMA_MAPS_KEYS,
+    },
+};
+
+const TYPE_KEY: &str = "type";
+const NULL_TYPE: &str = "null";
+const NULLABLE_KEY: &str = "nullable";
+const ANY_OF_KEY: &str = "anyOf";
+/// Keywords of OpenAPI 3.1 schema which are absent in OpenAPI 3.0 model, they kept in extension
+const JSON_SCHEMA_KEYWORDS: [&str; 10] = [
+    "const",
+    "examples",
+    "prefixItems",
+    "contentMediaType",
+    "contentEncoding",
+    "unevaluatedProperties",
+    "dependentSchemas",
+    "if",
+    "then",
+    "else",
+];
+
+/// Keywords of JSON Schema 2020-12 which have no visitors, they are dropped with warning
+const DROPPED_KEYWORDS: [&str; 6] = [
+    "unevaluatedItems",
+    "contains",
+    "minContains",
+    "maxContains",
+    "propertyNames",
+    "dependentRequired",
+];
+/// Keys of schema where boolean value is allowed by OpenAPI 3.0 model
+const BOOLEAN_SCHEMA_KEYS: [&str; 2] = ["additionalProperties", "unevaluatedProperties"];
+
+/// OpenAPI 3.1 schemas are JSON Schema 2020-12 ones, bare JSON Schema is given by input kind
+pub fn is_json_schema_dialect(spec: &Value, input_kind: InputKind) -> bool {
+    match input_kind {
+        InputKind::JsonSchema => true,
+        InputKind::Openapi => spec
+            .get("openapi")
+            .and_then(Value::as_str)
+            .is_some_and(|it| it.starts_with("3.1")),
+    }
+}
+
+/// Normalizes every schema of document (spec or its part which is not a schema) to OpenAPI 3.0 model
+pub fn normalize_document(value: &mut Value) {
+    normalize_node(value, false);
+}
+
+fn normalize_node(value: &mut Value, in_named_map: bool) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match key.as_str() {
+                    key if spec_keys::is_literal(key, child, in_named_map) => {}
+                    "schema" if !in_named_map => normalize_schema(child),
+                    SCHEMAS_KEY if !in_named_map => {
+                        if let Value::Object(schemas) = child {
+                            schemas.values_mut().for_each(normalize_schema);
+                        }
+                    }
+                    key => normalize_node(child, spec_keys::is_named_map(key, in_named_map)),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|it| normalize_node(it, false)),
+        _ => {}
+    }
+}
+
+/// Normalizes schema with subschemas: boolean schemas become objects, type arrays become nullable flag,
+/// numeric exclusive bounds become boolean ones, keywords unknown to OpenAPI 3.0 model are moved to extension
+pub fn normalize_schema(value: &mut Value) {
+    if let Value::Bool(allowed) = value {
+        // `true` allows any instance, `false` allows none
+        *value = if *allowed {
+            Value::Object(Map::new())
+        } else {
+            json!({ "not": {} })
+        };
+        return;
+    }
+    let Value::Object(schema) = value else {
+        return;
+    };
+
+    for (key, child) in schema.iter_mut() {
+        match key.as_str() {
+            key if SUBSCHEMA_MAPS_KEYS.contains(&key) => {
+                if let Value::Object(schemas) = child {
+                    schemas.values_mut().for_each(normalize_schema);
+                }
+            }
+            key if SUBSCHEMA_LISTS_KEYS.contains(&key) => {
+                if let Value::Array(schemas) = child {
+                    schemas.iter_mut().for_each(normalize_schema);
+                }
+            }
+            key if BOOLEAN_SCHEMA_KEYS.contains(&key) && child.is_boolean() => {}
+            key if SUBSCHEMA_KEYS.contains(&key) => normalize_schema(child),
+            _ => {}
+        }
+    }
+
+    for key in DROPPED_KEYWORDS {
+        if schema.remove(key).is_some() {
+            warn!("JSON Schema keyword [{}] is not supported and dropped", key);
+        }
+    }
+
+    normalize_type(schema);
+    normalize_exclusive_bound(schema, "exclusiveMinimum", "minimum");
+    normalize_exclusive_bound(schema, "exclusiveMaximum", "maximum");
+
+    let keywords: Map<String, Value> = JSON_SCHEMA_KEYWORDS
+        .iter()
+        .filter_map(|key| schema.remove(*key).map(|it| (key.to_string(), it)))
+        .collect();
+    if !keywords.is_empty() {
+        schema.insert(
+            EXTENSION_FOR_JSON_SCHEMA_KEYWORDS.to_owned(),
+            Value::Object(keywords),
+        );
+    }
+}
+
+/// Extension with JSON Schema keywords is internal, its keywords are given to own visitors
+pub fn strip_keywords_extension(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.remove(EXTENSION_FOR_JSON_SCHEMA_KEYWORDS);
+            map.values_mut().for_each(strip_keywords_extension);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_keywords_extension),
+        _ => {}
+    }
+}
+
+/// `type: [string, "null"]` is `type: string` with `nullable: true`, several types are alternatives of anyOf
+fn normalize_type(schema: &mut Map<String, Value>) {
+    let types: Vec<String> = match schema.get(TYPE_KEY) {
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect(),
+        Some(Value::String(it)) if it == NULL_TYPE => vec![it.clone()],
+        _ => return,
+    };
+
+    schema.remove(TYPE_KEY);
+    let (nulls, types): (Vec<String>, Vec<String>) =
+        types.into_iter().partition(|it| it == NULL_TYPE);
+    if !nulls.is_empty() {
+        schema.insert(NULLABLE_KEY.to_owned(), Value::Bool(true));
+    }
+
+    match types.as_slice() {
+        [] => {}
+        [single] => {
+            schema.insert(TYPE_KEY.to_owned(), Value::String(single.clone()));
+        }
+        _ if schema.contains_key(ANY_OF_KEY) => {
+            debug!("Types [{:?}] are dropped, schema has anyOf already", types);
+        }
+        _ => {
+            let alternatives = types
+                .into_iter()
+                .map(|it| {
+                    let mut alternative = Map::new();
+                    alternative.insert(TYPE_KEY.to_owned(), Value::String(it));
+                    Value::Object(alternative)
+                })
+                .collect();
+            schema.insert(ANY_OF_KEY.to_owned(), Value::Array(alternatives));
+        }
+    }
+}
+
+/// `exclusiveMinimum: 5` is `minimum: 5` with `exclusiveMinimum: true`
+fn normalize_exclusive_bound(
+    schema: &mut Map<String, Value>,
+    exclusive_key: &str,
+    bound_key: &str,
+) {
+    if let Some(Value::Number(bound)) = schema.get(exclusive_key) {
+        let bound = Value::Number(bound.clone());
+        schema.insert(bound_key.to_owned(), bound);
+        schema.insert(exclusive_key.to_owned(), Value::Bool(true));
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::Mutex,
+};
+
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use log::{info, warn};
+use mlua::{Function, LuaSerdeExt, MultiValue, Table};
+use serde_json::Value;
+
+use crate::{
+    enums::common::{LintSeverity, Script},
+    holders::context::{get_lua_vm, LINT_RULES_VARIABLE_NAME_IN_LUA},
+    services::{locations, merge, visitors},
+    structs::common::{LintDiagnostic, Linter, ParsedSpec, VisitOutput},
+    Commands,
+};
+
+const OPERATION_DESCRIPTION_RULE: &str = "operation-description";
+const OPERATION_ID_RULE: &str = "operation-operationId";
+const PARAMETER_DESCRIPTION_RULE: &str = "parameter-description";
+const SCHEMA_DESCRIPTION_RULE: &str = "schema-description";
+const PROPERTY_CAMEL_CASE_RULE: &str = "property-camel-case";
+const NO_INLINE_SCHEMA_RULE: &str = "no-inline-schema";
+const BUILT_IN_RULES: [&str; 6] = [
+    OPERATION_DESCRIPTION_RULE,
+    OPERATION_ID_RULE,
+    PARAMETER_DESCRIPTION_RULE,
+    SCHEMA_DESCRIPTION_RULE,
+    PROPERTY_CAMEL_CASE_RULE,
+    NO_INLINE_SCHEMA_RULE,
+];
+
+/// Spec is prepared as for translation, so Lua rules can use SPEC and filters and merge are applied
+pub fn lint_command(command: &Commands) -> Result<()> {
+    if let Commands::Lint {
+        spec: spec_paths,
+        rules,
+        lua_rules,
+        merge_conflict,
+    } = command
+    {
+        info!("Lint start for [{:?}]", spec_paths);
+        let spec_paths = merge::expand_spec_paths(spec_paths)?;
+        let (parsed_spec, openapi, webhooks) =
+            visitors::load_openapi(&spec_paths, *merge_conflict)?;
+
+        let mut severities: HashMap<String, LintSeverity> = BUILT_IN_RULES
+            .iter()
+            .map(|it| (it.to_string(), LintSeverity::Warn))
+            .collect();
+        if let Some(lua_rules_path) = lua_rules {
+            severities.extend(load_lua_rules(lua_rules_path)?);
+        }
+        for (rule, severity) in rules {
+            if !severities.contains_key(rule) {
+                return Err(anyhow!("Unknown lint rule [{}]", rule));
+            }
+            severities.insert(rule.clone(), *severity);
+        }
+
+        let linter = Mutex::new(Linter {
+            severities,
+            visited_locations: parsed_spec.visited_locations.clone(),
+            diagnostics: Vec::new(),
+        });
+        visitors::visit_spec(
+            &parsed_spec,
+            &VisitOutput::Lint(&linter),
+            &openapi,
+            &webhooks,
+        )
+        .map_err(|error| visitors::add_location_context(&parsed_spec, error))?;
+
+        let linter = linter.into_inner().expect("Could not lock linter");
+        report_diagnostics(&parsed_spec, linter.diagnostics)?;
+        info!("Lint end for [{:?}]", spec_paths);
+        Ok(())
+    } else {
+        Err(anyhow!("Expected a Lint command"))
+    }
+}
+
+/// Arguments of visitor are checked by every rule for this visitor
+pub fn check_visit(linter: &Mutex<Linter>, script: &Script, args: &Value) -> Result<()> {
+    let visitor = serde_json::to_value(script)?;
+    let visitor = visitor.as_str().unwrap_or_default();
+    let args = match args {
+        Value::Array(args) => args.clone(),
+        arg => vec![arg.clone()],
+    };
+
+    let mut broken = check_built_in_rules(visitor, &args);
+    broken.extend(check_lua_rules(visitor, &args)?);
+
+    let mut linter = linter.lock().expect("Could not lock linter");
+    let location = linter
+        .visited_locations
+        .lock()
+        .expect("Could not lock visited locations")
+        .last()
+        .cloned();
+    for (rule, message) in broken {
+        let severity = linter
+            .severities
+            .get(&rule)
+            .copied()
+            .unwrap_or(LintSeverity::Warn);
+        if severity != LintSeverity::Off {
+            linter.diagnostics.push(LintDiagnostic {
+                rule,
+                severity,
+                location: location.clone(),
+                message,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_built_in_rules(visitor: &str, args: &[Value]) -> Vec<(String, String)> {
+    let mut broken = Vec::new();
+    let mut add = |rule: &str, message: String| broken.push((rule.to_owned(), message));
+
+    match visitor {
+        _ if visitor.ends_with("OperationStart") => {
+            let operation = &args[0];
+            let name = operation
+                .get("operationId")
+                .or_else(|| operation.get("summary"))
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            if !has_text(operation, "description") {
+                add(
+                    OPERATION_DESCRIPTION_RULE,
+                    format!("Operation [{}] has no description", name),
+                );
+            }
+            if !has_text(operation, "operationId") {
+                add(
+                    OPERATION_ID_RULE,
+                    format!("Operation [{}] has no operationId", name),
+                );
+            }
+        }
+        "visitParameterDataStart" => {
+            let parameter = &args[0];
+            if !has_text(parameter, "description") {
+                add(
+                    PARAMETER_DESCRIPTION_RULE,
+                    format!(
+                        "Parameter [{}] has no description",
+                        parameter
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                    ),
+                );
+            }
+        }
+        // Only named schemas are components, others are described by their holders
+        "visitSchemaStart" => {
+            if let Some(name) = args[0].as_str() {
+                if !has_text(&args[1], "description") {
+                    add(
+                        SCHEMA_DESCRIPTION_RULE,
+                        format!("Schema [{}] has no description", name),
+                    );
+                }
+            }
+        }
+        "visitObjectPropertyStart" | "visitObjectPropertyReferenceStart" => {
+            if let Some(name) = args[0].as_str().filter(|it| !is_camel_case(it)) {
+                add(
+                    PROPERTY_CAMEL_CASE_RULE,
+                    format!("Property [{}] is not camelCase", name),
+                );
+            }
+        }
+        "visitMediaTypeStart" => {
+            let schema = args[1].get("schema");
+            let is_inline_object = schema.is_some_and(|it| {
+                it.get("$ref").is_none()
+                    && (it.get("properties").is_some()
+                        || it.get("type").and_then(Value::as_str) == Some("object"))
+            });
+            if is_inline_object {
+                add(
+                    NO_INLINE_SCHEMA_RULE,
+                    format!(
+                        "Inline object schema of media type [{}] should be a component",
+                        args[0].as_str().unwrap_or_default()
+                    ),
+                );
+            }
+        }
+        _ => {}
+    }
+    broken
+}
+
+/// Check function of Lua rule returns nil, message or list of messages
+fn check_lua_rules(visitor: &str, args: &[Value]) -> Result<Vec<(String, String)>> {
+    let lua_vm = get_lua_vm();
+    let Some(rules) = lua_vm
+        .globals()
+        .get::<_, Option<Table>>(LINT_RULES_VARIABLE_NAME_IN_LUA)?
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut broken = Vec::new();
+    for pair in rules.pairs::<String, Table>() {
+        let (rule, definition) = pair?;
+        if definition.get::<_, Option<String>>("visitor")?.as_deref() != Some(visitor) {
+            continue;
+        }
+        let check: Function = definition
+            .get("check")
+            .with_context(|| format!("Lua lint rule [{}] has no check function", rule))?;
+        let lua_args = args
+            .iter()
+            .map(|it| lua_vm.to_value(it))
+            .collect::<Result<Vec<_>, _>>()?;
+        let result: mlua::Value = check
+            .call(MultiValue::from_vec(lua_args))
+            .with_context(|| format!("Failed to call Lua lint rule [{}]", rule))?;
+
+        let messages: Vec<String> = match result {
+            mlua::Value::Nil => Vec::new(),
+            mlua::Value::Table(messages) => messages
+                .sequence_values::<String>()
+                .collect::<Result<_, _>>()?,
+            message => vec![lua_vm.from_value(message)?],
+        };
+        broken.extend(messages.into_iter().map(|it| (rule.clone(), it)));
+    }
+    Ok(broken)
+}
+
+/// Lua script returns table of rules by name, severity of rule is warn if it is not set,
+/// rules are loaded after SPEC global is set
+fn load_lua_rules(lua_rules_path: &Path) -> Result<HashMap<String, LintSeverity>> {
+    let code = fs::read_to_string(lua_rules_path)
+        .with_context(|| format!("Could not open Lua lint rules [{:?}]", lua_rules_path))?;
+
+    let lua_vm = get_lua_vm();
+    let rules: Table = lua_vm
+        .load(&code)
+        .set_name(lua_rules_path.to_string_lossy())
+        .eval()
+        .with_context(|| format!("Could not load Lua lint rules [{:?}]", lua_rules_path))?;
+
+    let mut severities = HashMap::new();
+    for pair in rules.clone().pairs::<String, Table>() {
+        let (rule, definition) = pair?;
+        let severity = match definition.get::<_, Option<String>>("severity")? {
+            Some(severity) => LintSeverity::from_str(&severity, true).map_err(|error| {
+                anyhow!("Invalid severity of Lua lint rule [{}]: {}", rule, error)
+            })?,
+            None => LintSeverity::Warn,
+        };
+        severities.insert(rule, severity);
+    }
+    lua_vm
+        .globals()
+        .set(LINT_RULES_VARIABLE_NAME_IN_LUA, rules)?;
+    Ok(severities)
+}
+
+/// Node visited several times by references is reported once, errors fail lint
+fn report_diagnostics(parsed_spec: &ParsedSpec, diagnostics: Vec<LintDiagnostic>) -> Result<()> {
+    let mut seen = HashSet::new();
+    let diagnostics: Vec<LintDiagnostic> = diagnostics
+        .into_iter()
+        .filter(|it| seen.insert(it.clone()))
+        .collect();
+
+    let errors = diagnostics
+        .iter()
+        .filter(|it| it.severity == LintSeverity::Error)
+        .count();
+    let lines: Vec<String> = diagnostics
+        .iter()
+        .map(|it| {
+            format!(
+                "- [{} {}] [{}] {}",
+                if it.severity == LintSeverity::Error {
+                    "error"
+                } else {
+                    "warn"
+                },
+                it.rule,
+                it.location
+                    .as_ref()
+                    .map(|location| locations::describe_node(parsed_spec, location))
+                    .unwrap_or_default(),
+                it.message
+            )
+        })
+        .collect();
+
+    if errors > 0 {
+        return Err(anyhow!(
+            "Lint found {} error(s) and {} warning(s):\n{}",
+            errors,
+            lines.len() - errors,
+            lines.join("\n")
+        ));
+    }
+    if !lines.is_empty() {
+        warn!(
+            "Lint found {} warning(s):\n{}",
+            lines.len(),
+            lines.join("\n")
+        );
+    }
+    Ok(())
+}
+
+fn has_text(value: &Value, key: &str) -> bool {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .is_some_and(|it| !it.trim().is_empty())
+}
+
+fn is_camel_case(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|it| it.is_ascii_lowercase())
+        && name.chars().all(|it| it.is_ascii_alphanumeric())
+}
@@ -0,0 +1,234 @@
+use std::{collections::HashMap, env, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use json_pointer::JsonPointer;
+use serde_json::Value;
+use url::Url;
+use yaml_rust2::{parser::Parser, Event};
+
+use crate::{
+    services::{references, spec_keys},
+    structs::common::{ParsedSpec, SourcePosition},
+};
+
+enum Frame {
+    Mapping {
+        pointer: String,
+        key: Option<(String, SourcePosition)>,
+    },
+    Sequence {
+        pointer: String,
+        index: usize,
+    },
+}
+
+enum Node {
+    Scalar(String),
+    Collection(bool),
+    Alias,
+}
+
+/// Last visited node as `file:line:col`, node without own position is described by nearest parent
+pub fn describe_visited_location(parsed_spec: &ParsedSpec) -> Option<String> {
+    describe_location(parsed_spec, &parsed_spec.get_visited_location()?)
+}
+
+/// Node by absolute reference `document#pointer` as `file:line:col`
+pub fn describe_location(parsed_spec: &ParsedSpec, location: &str) -> Option<String> {
+    let (document, fragment) = references::split_reference(location);
+
+    let (document, mut pointer) =
+        if let Some((document, anchored)) = parsed_spec.find_identifier(location) {
+            (document, JsonPointer::new(anchored).to_string())
+        } else {
+            let relative = references::parse_json_pointer(fragment).ok()?.to_string();
+            match parsed_spec.find_identifier(document) {
+                Some((document, identified)) => (
+                    document,
+                    format!("{}{}", JsonPointer::new(identified), relative),
+                ),
+                None => (document.to_owned(), relative),
+            }
+        };
+
+    loop {
+        if let Some(position) = parsed_spec.find_location(&document, &pointer) {
+            return Some(format!(
+                "{}:{}:{}",
+                get_document_name(&position.document),
+                position.line,
+                position.column
+            ));
+        }
+        pointer.truncate(pointer.rfind('/')?);
+    }
+}
+
+/// Node as `file:line:col` with its pointer, pointer in root spec is local
+pub fn describe_node(parsed_spec: &ParsedSpec, location: &str) -> String {
+    let (document, fragment) = references::split_reference(location);
+    let is_root = references::absolute_reference("", parsed_spec)
+        .is_ok_and(|root| references::split_reference(&root).0 == document);
+    let pointer = if is_root {
+        format!("#{}", fragment)
+    } else {
+        location.to_owned()
+    };
+    match describe_location(parsed_spec, location) {
+        Some(position) => format!("{} {}", position, pointer),
+        None => pointer,
+    }
+}
+
+/// Local documents are named by path relative to working dir
+fn get_document_name(document_uri: &str) -> String {
+    Url::parse(document_uri)
+        .ok()
+        .filter(|it| it.scheme() == "file")
+        .and_then(|it| it.to_file_path().ok())
+        .map(|path| {
+            env::current_dir()
+                .ok()
+                .and_then(|dir| path.strip_prefix(dir).ok().map(|it| it.to_path_buf()))
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        })
+        .unwrap_or_else(|| document_uri.to_owned())
+}
+
+/// Positions of nodes of YAML (or JSON) document by JSON pointer, mapping entries positioned at their key
+pub fn index_locations(
+    document_uri: &str,
+    content: &str,
+) -> Result<HashMap<String, SourcePosition>> {
+    let document: Arc<str> = Arc::from(document_uri);
+    let mut locations = HashMap::new();
+    let mut stack: Vec<Frame> = Vec::new();
+
+    // serde_yaml has no spans, so events of separate parser with positions are used
+    let mut parser = Parser::new_from_str(content);
+    loop {
+        let (event, mark) = parser
+            .next_token()
+            .map_err(|error| anyhow!("Could not parse YAML for source locations: {}", error))?;
+        let position = SourcePosition {
+            document: Arc::clone(&document),
+            line: mark.line(),
+            column: mark.col() + 1,
+        };
+
+        let node = match event {
+            Event::Scalar(value, ..) => Node::Scalar(value),
+            Event::MappingStart(..) => Node::Collection(true),
+            Event::SequenceStart(..) => Node::Collection(false),
+            Event::Alias(_) => Node::Alias,
+            Event::MappingEnd | Event::SequenceEnd => {
+                stack.pop();
+                continue;
+            }
+            // Only first document is a spec
+            Event::DocumentEnd | Event::StreamEnd => break,
+            _ => continue,
+        };
+        add_node(&mut stack, &mut locations, node, position);
+    }
+
+    Ok(locations)
+}
+
+/// Positions of nodes which are at the same pointers in source and final document, nodes added, moved
+/// or shifted in arrays by overlays or upgrade of Swagger 2.0 have no source and are located by parent
+pub fn retain_sourced(
+    locations: HashMap<String, SourcePosition>,
+    source: &Value,
+    target: &Value,
+) -> HashMap<String, SourcePosition> {
+    let mut retained = HashMap::new();
+    collect_sourced(&locations, source, target, String::new(), &mut retained);
+    retained
+}
+
+fn collect_sourced(
+    locations: &HashMap<String, SourcePosition>,
+    source: &Value,
+    target: &Value,
+    pointer: String,
+    retained: &mut HashMap<String, SourcePosition>,
+) {
+    if let Some(position) = locations.get(&pointer) {
+        retained.insert(pointer.clone(), position.clone());
+    }
+    match (source, target) {
+        (Value::Object(source), Value::Object(target)) => {
+            for (key, target_child) in target {
+                if let Some(source_child) = source.get(key) {
+                    let child_pointer = spec_keys::child_location(&pointer, key);
+                    collect_sourced(
+                        locations,
+                        source_child,
+                        target_child,
+                        child_pointer,
+                        retained,
+                    );
+                }
+            }
+        }
+        // Items are at the same indices if none is added or removed, otherwise only equal ones are kept
+        (Value::Array(source), Value::Array(target)) => {
+            let same_length = source.len() == target.len();
+            for (index, (source_item, target_item)) in source.iter().zip(target).enumerate() {
+                if same_length || source_item == target_item {
+                    let item_pointer = format!("{}/{}", pointer, index);
+                    collect_sourced(locations, source_item, target_item, item_pointer, retained);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn add_node(
+    stack: &mut Vec<Frame>,
+    locations: &mut HashMap<String, SourcePosition>,
+    node: Node,
+    position: SourcePosition,
+) {
+    let located = match stack.last_mut() {
+        None => Some((String::new(), position)),
+        Some(Frame::Mapping { pointer, key }) => match key.take() {
+            Some((name, key_position)) => {
+                Some((spec_keys::child_location(pointer, &name), key_position))
+            }
+            None => {
+                // Node is key of mapping entry, complex keys are not addressable by pointer
+                let name = match &node {
+                    Node::Scalar(value) => value.clone(),
+                    _ => String::new(),
+                };
+                *key = Some((name, position));
+                None
+            }
+        },
+        Some(Frame::Sequence { pointer, index }) => {
+            *index += 1;
+            Some((format!("{}/{}", pointer, *index - 1), position))
+        }
+    };
+
+    let pointer = match located {
+        Some((pointer, position)) => {
+            locations.insert(pointer.clone(), position);
+            pointer
+        }
+        None => String::from("?"),
+    };
+
+    if let Node::Collection(is_mapping) = node {
+        stack.push(if is_mapping {
+            Frame::Mapping { pointer, key: None }
+        } else {
+            Frame::Sequence { pointer, index: 0 }
+        });
+    }
+}
@@ -0,0 +1,464 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info, warn};
+use serde_json::{json, Map, Value};
+use url::Url;
+
+use crate::{
+    enums::common::MergeConflict,
+    services::{
+        references,
+        spec_keys::{self, COMPONENTS_KEY, OPERATION_KEYS, PATHS_KEY, REFERENCE_KEY, WEBHOOKS_KEY},
+    },
+    structs::common::{ParsedSpec, SourcePosition},
+};
+
+const TAGS_KEY: &str = "tags";
+const SERVERS_KEY: &str = "servers";
+const SECURITY_KEY: &str = "security";
+/// Top level maps which are unioned by keys, values of the same key are merged one level down
+const MERGED_MAPS_KEYS: [&str; 2] = [PATHS_KEY, WEBHOOKS_KEY];
+
+struct Merger {
+    merged: Map<String, Value>,
+    strategy: MergeConflict,
+    /// Spec which brought top level entry or component, by its pointer
+    origins: HashMap<String, PathBuf>,
+    conflicts: Vec<String>,
+    /// Source positions of merged nodes, every node is located in spec which brought it
+    locations: HashMap<String, SourcePosition>,
+    /// Pointers of nodes in merging spec to their pointers in merged one
+    placed: HashMap<String, String>,
+}
+
+/// Paths which are not files are taken as glob patterns, matched files are sorted
+pub fn expand_spec_paths(spec_paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut expanded = Vec::new();
+    for spec_path in spec_paths {
+        if spec_path.exists() {
+            expanded.push(spec_path.clone());
+            continue;
+        }
+
+        let pattern = spec_path
+            .to_str()
+            .ok_or_else(|| anyhow!("Spec path [{:?}] is not valid UTF-8", spec_path))?;
+        let mut matched: Vec<PathBuf> = glob::glob(pattern)
+            .with_context(|| format!("Invalid spec glob pattern [{}]", pattern))?
+            .collect::<Result<_, _>>()
+            .with_context(|| format!("Could not read paths matched by [{}]", pattern))?;
+        if matched.is_empty() {
+            return Err(anyhow!("Spec [{}] is not found", pattern));
+        }
+        matched.sort();
+        expanded.extend(matched);
+    }
+
+    let mut seen = HashSet::new();
+    expanded.retain(|it| seen.insert(it.clone()));
+    Ok(expanded)
+}
+
+/// Several specs are merged into first one: paths, webhooks, tags and components are unioned,
+/// servers and security of other specs are moved to their paths and operations, the rest is taken from first spec
+pub fn load_merged_spec(spec_paths: &[PathBuf], strategy: MergeConflict) -> Result<ParsedSpec> {
+    let (first_path, other_paths) = spec_paths
+        .split_first()
+        .ok_or_else(|| anyhow!("No spec to translate"))?;
+    if other_paths.is_empty() {
+        return references::load_spec(first_path);
+    }
+
+    let (mut first_spec, locations) = references::read_spec(first_path)?;
+    let first_uri = absolutize_references(&mut first_spec, first_path)?;
+    localize_references(&mut first_spec, first_uri.as_str(), &HashMap::new());
+    let Value::Object(merged) = first_spec else {
+        return Err(anyhow!("Spec is not an object [{:?}]", first_path));
+    };
+    let mut merger = Merger {
+        merged,
+        strategy,
+        origins: HashMap::new(),
+        conflicts: Vec::new(),
+        locations,
+        placed: HashMap::new(),
+    };
+    merger.add_origins(first_path);
+
+    for spec_path in other_paths {
+        info!("Merge spec [{:?}] into [{:?}]", spec_path, first_path);
+        let (spec, locations) = references::read_spec(spec_path)?;
+        merger
+            .merge_spec(spec_path, spec)
+            .with_context(|| format!("Could not merge spec [{:?}]", spec_path))?;
+        merger.add_locations(locations);
+    }
+
+    if !merger.conflicts.is_empty() {
+        return Err(anyhow!(
+            "Merged specs have conflicts, components can be renamed with --merge-conflict rename:\n{}",
+            merger.conflicts.join("\n")
+        ));
+    }
+
+    references::new_parsed_spec(first_path, Value::Object(merger.merged), merger.locations)
+}
+
+impl Merger {
+    fn add_origins(&mut self, spec_path: &Path) {
+        let pointers: Vec<String> = MERGED_MAPS_KEYS
+            .iter()
+            .filter_map(|key| {
+                self.merged
+                    .get(*key)
+                    .and_then(Value::as_object)
+                    .map(|it| (key, it))
+            })
+            .flat_map(|(key, entries)| entries.keys().map(move |name| get_pointer(&[key, name])))
+            .chain(
+                get_components(self.merged.get(COMPONENTS_KEY))
+                    .into_iter()
+                    .map(|(kind, name)| get_pointer(&[COMPONENTS_KEY, &kind, &name])),
+            )
+            .collect();
+        for pointer in pointers {
+            self.origins.insert(pointer, spec_path.to_owned());
+        }
+    }
+
+    fn merge_spec(&mut self, spec_path: &Path, mut spec: Value) -> Result<()> {
+        let uri = absolutize_references(&mut spec, spec_path)?;
+        localize_references(&mut spec, uri.as_str(), &HashMap::new());
+        let renames = self.get_renames(spec_path, &spec);
+        if !renames.is_empty() {
+            localize_references(&mut spec, uri.as_str(), &renames);
+        }
+
+        let Value::Object(mut spec) = spec else {
+            return Err(anyhow!("Spec is not an object"));
+        };
+        self.move_servers(spec_path, &mut spec);
+        self.move_security(spec_path, &mut spec);
+        self.warn_ignored(spec_path, &spec);
+
+        for key in MERGED_MAPS_KEYS {
+            if let Some(Value::Object(entries)) = spec.get(key) {
+                for (name, entry) in entries {
+                    let pointer = get_pointer(&[key, name]);
+                    self.merge_entry(spec_path, &pointer, &[key, name], entry.clone());
+                }
+            }
+        }
+
+        if let Some(Value::Object(components)) = spec.get(COMPONENTS_KEY) {
+            for (kind, named) in components {
+                let Value::Object(named) = named else {
+                    continue;
+                };
+                for (name, component) in named {
+                    let pointer = get_pointer(&[COMPONENTS_KEY, kind, name]);
+                    let name = renames.get(&(kind.clone(), name.clone())).unwrap_or(name);
+                    self.merge_entry(
+                        spec_path,
+                        &pointer,
+                        &[COMPONENTS_KEY, kind, name],
+                        component.clone(),
+                    );
+                }
+            }
+        }
+
+        if let Some(Value::Array(tags)) = spec.get(TAGS_KEY) {
+            let merged_tags = self
+                .merged
+                .entry(TAGS_KEY)
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(merged_tags) = merged_tags {
+                for (index, tag) in tags.iter().enumerate() {
+                    if !merged_tags
+                        .iter()
+                        .any(|it| it.get("name") == tag.get("name"))
+                    {
+                        self.placed.insert(
+                            format!("/{}/{}", TAGS_KEY, index),
+                            format!("/{}/{}", TAGS_KEY, merged_tags.len()),
+                        );
+                        merged_tags.push(tag.clone());
+                    }
+                }
+            }
+        }
+
+        for (key, value) in spec {
+            if key.starts_with("x-") && !self.merged.contains_key(&key) {
+                self.placed
+                    .insert(get_pointer(&[&key]), get_pointer(&[&key]));
+                self.merged.insert(key, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Servers of spec apply to its paths only, so they are moved to path items without own servers
+    fn move_servers(&self, spec_path: &Path, spec: &mut Map<String, Value>) {
+        let servers = get_servers(spec.remove(SERVERS_KEY));
+        if servers == get_servers(self.merged.get(SERVERS_KEY).cloned()) {
+            return;
+        }
+        debug!("Servers of [{:?}] are moved to its paths", spec_path);
+        if let Some(Value::Object(paths)) = spec.get_mut(PATHS_KEY) {
+            for path_item in paths.values_mut().filter_map(Value::as_object_mut) {
+                if !path_item.contains_key(REFERENCE_KEY) {
+                    path_item
+                        .entry(SERVERS_KEY)
+                        .or_insert_with(|| servers.clone());
+                }
+            }
+        }
+    }
+
+    /// Security requirements of spec apply to its operations only, so they are moved to operations
+    /// without own requirements, absent requirements mean no security
+    fn move_security(&self, spec_path: &Path, spec: &mut Map<String, Value>) {
+        let security = spec.remove(SECURITY_KEY).unwrap_or_else(|| json!([]));
+        let merged_security = self.merged.get(SECURITY_KEY).cloned();
+        if security == merged_security.unwrap_or_else(|| json!([])) {
+            return;
+        }
+        debug!("Security of [{:?}] is moved to its operations", spec_path);
+        for key in MERGED_MAPS_KEYS {
+            let Some(Value::Object(path_items)) = spec.get_mut(key) else {
+                continue;
+            };
+            for path_item in path_items.values_mut().filter_map(Value::as_object_mut) {
+                for operation_key in OPERATION_KEYS {
+                    if let Some(Value::Object(operation)) = path_item.get_mut(operation_key) {
+                        operation
+                            .entry(SECURITY_KEY)
+                            .or_insert_with(|| security.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Top level values other than merged ones are taken from first spec
+    fn warn_ignored(&self, spec_path: &Path, spec: &Map<String, Value>) {
+        for (key, value) in spec {
+            let is_merged = MERGED_MAPS_KEYS.contains(&key.as_str())
+                || [COMPONENTS_KEY, TAGS_KEY].contains(&key.as_str())
+                || key.starts_with("x-");
+            if !is_merged && self.merged.get(key) != Some(value) {
+                warn!(
+                    "Top level [{}] of [{:?}] differs from one of first spec and is ignored",
+                    key, spec_path
+                );
+            }
+        }
+    }
+
+    /// Positions of nodes brought by merged spec are moved to their pointers in merged spec
+    fn add_locations(&mut self, locations: HashMap<String, SourcePosition>) {
+        for (pointer, position) in locations {
+            let mut prefix = pointer.as_str();
+            let placed = loop {
+                if let Some(placed) = self.placed.get(prefix) {
+                    break Some(format!("{}{}", placed, &pointer[prefix.len()..]));
+                }
+                match prefix.rfind('/') {
+                    Some(index) => prefix = &prefix[..index],
+                    None => break None,
+                }
+            };
+            if let Some(placed) = placed {
+                self.locations.insert(placed, position);
+            }
+        }
+        self.placed.clear();
+    }
+
+    /// Components with taken names and other content get unique names if strategy is rename
+    fn get_renames(&self, spec_path: &Path, spec: &Value) -> HashMap<(String, String), String> {
+        let mut renames = HashMap::new();
+        if self.strategy != MergeConflict::Rename {
+            return renames;
+        }
+
+        // Own names of merging spec are taken too, so renamed component does not shadow one of them
+        let mut taken: HashSet<(String, String)> = get_components(self.merged.get(COMPONENTS_KEY))
+            .into_iter()
+            .chain(get_components(spec.get(COMPONENTS_KEY)))
+            .collect();
+        for (kind, name) in get_components(spec.get(COMPONENTS_KEY)) {
+            let existing = self.get_merged(&[COMPONENTS_KEY, &kind, &name]);
+            let component = spec
+                .get(COMPONENTS_KEY)
+                .and_then(|it| it.get(&kind))
+                .and_then(|it| it.get(&name));
+            if existing.is_none() || existing == component {
+                continue;
+            }
+
+            let mut unique_name = name.clone();
+            let mut index = 1;
+            while taken.contains(&(kind.clone(), unique_name.clone())) {
+                unique_name = format!("{}{}", name, index);
+                index += 1;
+            }
+            debug!(
+                "Component [{}/{}] of [{:?}] renamed to [{}]",
+                kind, name, spec_path, unique_name
+            );
+            taken.insert((kind.clone(), unique_name.clone()));
+            renames.insert((kind, name), unique_name);
+        }
+        renames
+    }
+
+    /// Entry absent in merged spec is added, the same one is skipped, objects are merged one level down
+    fn merge_entry(&mut self, spec_path: &Path, source: &str, keys: &[&str], entry: Value) {
+        let pointer = get_pointer(keys);
+        let Some((last, parents)) = keys.split_last() else {
+            return;
+        };
+
+        let mut parent = &mut self.merged;
+        for key in parents {
+            let child = parent
+                .entry(*key)
+                .or_insert_with(|| Value::Object(Map::new()));
+            if !child.is_object() {
+                *child = Value::Object(Map::new());
+            }
+            parent = child
+                .as_object_mut()
+                .expect("Merged parent is not an object");
+        }
+
+        match (parent.get_mut(*last), entry) {
+            (None, entry) => {
+                parent.insert((*last).to_owned(), entry);
+                self.placed.insert(source.to_owned(), pointer.clone());
+                self.origins.insert(pointer, spec_path.to_owned());
+            }
+            (Some(existing), entry) if *existing == entry => {}
+            // Path items of the same path are merged by operations
+            (Some(Value::Object(existing)), Value::Object(entry)) if keys[0] != COMPONENTS_KEY => {
+                for (key, value) in entry {
+                    match existing.get(&key) {
+                        None => {
+                            let key_pointer = get_pointer(&[&key]);
+                            self.placed.insert(
+                                format!("{}{}", source, key_pointer),
+                                format!("{}{}", pointer, key_pointer),
+                            );
+                            existing.insert(key, value);
+                        }
+                        Some(it) if *it == value => {}
+                        Some(_) => self.conflicts.push(describe_conflict(
+                            &format!("{}{}", pointer, get_pointer(&[&key])),
+                            spec_path,
+                            self.origins.get(&pointer),
+                        )),
+                    }
+                }
+            }
+            _ => self.conflicts.push(describe_conflict(
+                &pointer,
+                spec_path,
+                self.origins.get(&pointer),
+            )),
+        }
+    }
+
+    fn get_merged(&self, keys: &[&str]) -> Option<&Value> {
+        let (first, rest) = keys.split_first()?;
+        rest.iter()
+            .try_fold(self.merged.get(*first)?, |value, key| value.get(*key))
+    }
+}
+
+/// Relative references would point to other files after move into first spec, so every reference
+/// is made absolute, references to the spec itself are made local again by `localize_references`
+fn absolutize_references(spec: &mut Value, spec_path: &Path) -> Result<Url> {
+    let uri = references::get_spec_uri(spec_path)?;
+    references::rebase_references(spec, &uri, true)?;
+    Ok(uri)
+}
+
+/// References to merged spec become local ones, renamed components are referenced by new names
+fn localize_references(
+    value: &mut Value,
+    document_uri: &str,
+    renames: &HashMap<(String, String), String>,
+) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get_mut(REFERENCE_KEY) {
+                let (document, fragment) = references::split_reference(reference);
+                if document.is_empty() || document == document_uri {
+                    *reference = format!("#{}", rename_fragment(fragment, renames));
+                }
+            }
+            map.values_mut()
+                .for_each(|it| localize_references(it, document_uri, renames));
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|it| localize_references(it, document_uri, renames)),
+        _ => {}
+    }
+}
+
+fn rename_fragment(fragment: &str, renames: &HashMap<(String, String), String>) -> String {
+    let segments: Vec<&str> = fragment.splitn(5, '/').collect();
+    if let ["", COMPONENTS_KEY, kind, name, rest @ ..] = segments.as_slice() {
+        let name = spec_keys::unescape(name);
+        if let Some(renamed) = renames.get(&((*kind).to_owned(), name)) {
+            return std::iter::once(get_pointer(&[COMPONENTS_KEY, kind, renamed]))
+                .chain(rest.iter().map(|it| format!("/{}", it)))
+                .collect();
+        }
+    }
+    fragment.to_owned()
+}
+
+/// Absent servers mean server with `/` URL
+fn get_servers(servers: Option<Value>) -> Value {
+    servers
+        .filter(|it| it.as_array().is_some_and(|it| !it.is_empty()))
+        .unwrap_or_else(|| json!([{ "url": "/" }]))
+}
+
+/// Kind and name of every component
+fn get_components(components: Option<&Value>) -> Vec<(String, String)> {
+    components
+        .and_then(Value::as_object)
+        .map(|components| {
+            components
+                .iter()
+                .filter_map(|(kind, it)| it.as_object().map(|named| (kind, named)))
+                .flat_map(|(kind, named)| named.keys().map(|name| (kind.clone(), name.clone())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn describe_conflict(pointer: &str, spec_path: &Path, origin: Option<&PathBuf>) -> String {
+    format!(
+        "[{}] of [{:?}] differs from one of [{:?}]",
+        pointer,
+        spec_path,
+        origin.map(PathBuf::as_path).unwrap_or(Path::new(""))
+    )
+}
+
+fn get_pointer(keys: &[&str]) -> String {
+    keys.iter()
+        .map(|it| format!("/{}", spec_keys::escape(it)))
+        .collect()
+}
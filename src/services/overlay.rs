@@ -0,0 +1,158 @@
+use std::{
+    cmp::Ordering,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info, warn};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+use crate::{
+    services::spec_keys,
+    structs::common::{Overlay, OverlayAction},
+};
+
+const SUPPORTED_VERSION_PREFIX: &str = "1.";
+
+/// Overlays are applied in given order, every action sees result of previous ones
+pub fn apply_overlays(spec: &mut Value, overlay_paths: &[PathBuf]) -> Result<()> {
+    for overlay_path in overlay_paths {
+        let overlay = load_overlay(overlay_path)?;
+        info!("Apply overlay [{:?}]", overlay_path);
+
+        for (index, action) in overlay.actions.iter().enumerate() {
+            apply_action(spec, action).with_context(|| {
+                format!(
+                    "Could not apply action [{}] of overlay [{:?}]",
+                    index, overlay_path
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn load_overlay(overlay_path: &Path) -> Result<Overlay> {
+    let content = fs::read_to_string(overlay_path)
+        .with_context(|| format!("Could not open overlay [{:?}]", overlay_path))?;
+    let overlay: Overlay = serde_yaml::from_str(&content)
+        .with_context(|| format!("Could not parse overlay [{:?}]", overlay_path))?;
+
+    if !overlay.overlay.starts_with(SUPPORTED_VERSION_PREFIX) {
+        return Err(anyhow!(
+            "Overlay version [{}] is not supported [{:?}]",
+            overlay.overlay,
+            overlay_path
+        ));
+    }
+    Ok(overlay)
+}
+
+fn apply_action(spec: &mut Value, action: &OverlayAction) -> Result<()> {
+    let path = JsonPath::parse(&action.target)
+        .with_context(|| format!("Invalid JSONPath target [{}]", action.target))?;
+    let mut pointers: Vec<String> = path
+        .query_located(spec)
+        .locations()
+        .map(|it| it.to_json_pointer())
+        .collect();
+
+    if pointers.is_empty() {
+        warn!(
+            "Overlay target [{}] matches nothing{}",
+            action.target,
+            action
+                .description
+                .as_ref()
+                .map(|it| format!(" ({})", it))
+                .unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    if action.remove {
+        // Items of the same array are removed from last, so indices of remaining ones stay valid
+        pointers.sort_by(|a, b| compare_pointers(b, a));
+        for pointer in &pointers {
+            remove_node(spec, pointer)?;
+        }
+    } else if let Some(update) = &action.update {
+        for pointer in &pointers {
+            let node = spec
+                .pointer_mut(pointer)
+                .ok_or_else(|| anyhow!("Target node [{}] is not found", pointer))?;
+            merge_update(node, update);
+        }
+    }
+
+    debug!(
+        "Overlay target [{}] applied to [{:?}]",
+        action.target, pointers
+    );
+    Ok(())
+}
+
+/// Array indices are compared as numbers, descendants follow their ancestors
+fn compare_pointers(a: &str, b: &str) -> Ordering {
+    let mut a_tokens = a.split('/');
+    let mut b_tokens = b.split('/');
+    loop {
+        match (a_tokens.next(), b_tokens.next()) {
+            (Some(a_token), Some(b_token)) => {
+                let ordering = match (a_token.parse::<usize>(), b_token.parse::<usize>()) {
+                    (Ok(a_index), Ok(b_index)) => a_index.cmp(&b_index),
+                    _ => a_token.cmp(b_token),
+                };
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (a_token, b_token) => return a_token.is_some().cmp(&b_token.is_some()),
+        }
+    }
+}
+
+fn remove_node(spec: &mut Value, pointer: &str) -> Result<()> {
+    let (parent_pointer, token) = pointer
+        .rsplit_once('/')
+        .ok_or_else(|| anyhow!("Root of spec can not be removed"))?;
+    let key = spec_keys::unescape(token);
+
+    match spec.pointer_mut(parent_pointer) {
+        Some(Value::Object(map)) => {
+            map.remove(&key);
+        }
+        Some(Value::Array(items)) => {
+            let index: usize = key
+                .parse()
+                .with_context(|| format!("Invalid array index [{}]", pointer))?;
+            if index < items.len() {
+                items.remove(index);
+            }
+        }
+        _ => return Err(anyhow!("Parent of target node [{}] is not found", pointer)),
+    }
+    Ok(())
+}
+
+/// Objects are merged recursively, update of array is appended to it as one entry, other values are replaced
+fn merge_update(node: &mut Value, update: &Value) {
+    match (node, update) {
+        (Value::Object(target), Value::Object(update)) => {
+            for (key, value) in update {
+                match target.get_mut(key) {
+                    Some(existing) if existing.is_object() && value.is_object() => {
+                        merge_update(existing, value)
+                    }
+                    _ => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (Value::Array(target), update) => target.push(update.clone()),
+        (node, update) => *node = update.clone(),
+    }
+}
@@ -1,89 +1,561 @@
+use std::any::TypeId;
+use std::collections::HashMap;
 use std::fs;
 use std::fs::File;
+use std::io::Cursor;
 use std::io::Read;
+use std::path;
 use std::path::Path;
 use std::sync::Arc;
 
-use crate::holders::context::compute_if_absent;
-use crate::holders::context::CLIENT;
-use crate::structs::common::ParsedSpec;
+use crate::holders::context::CLI;
+use crate::services::http;
+use crate::services::json_schema;
+use crate::services::locations;
+use crate::services::overlay;
+use crate::services::spec_keys::{self, REFERENCE_KEY};
+use crate::services::swagger;
+use crate::structs::common::{ParsedSpec, SourcePosition};
 
 use anyhow::anyhow;
 use anyhow::Context;
 use anyhow::Result;
-use log::debug;
-use openapiv3::ReferenceOr;
+use json_pointer::IndexError;
+use json_pointer::JsonPointer;
+use json_pointer::ParseError;
+use log::{debug, warn};
+use openapiv3::{ReferenceOr, Schema};
+use percent_encoding::percent_decode_str;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 use url::Url;
 
-pub fn resolve_reference<T>(uri: &str, parsed_spec: &ParsedSpec) -> Result<&'static ReferenceOr<T>>
+const ID_KEY: &str = "$id";
+const ANCHOR_KEYS: [&str; 2] = ["$anchor", "$dynamicAnchor"];
+/// Chains are cached together with documents and items, so key differs from reference
+const CHAIN_KEY_PREFIX: &str = "chain:";
+/// Readable tail of cache file name is kept short, so the name fits file system limits
+const CACHE_FILE_NAME_SUFFIX_MAX_LEN: usize = 64;
+
+pub fn load_spec(spec_path: &Path) -> Result<ParsedSpec> {
+    let (spec_as_json, locations) = read_spec(spec_path)?;
+    new_parsed_spec(spec_path, spec_as_json, locations)
+}
+
+/// Spec as JSON after overlays and upgrade of Swagger 2.0, together with source positions of its nodes
+/// which are left in place by them
+pub fn read_spec(spec_path: &Path) -> Result<(Value, HashMap<String, SourcePosition>)> {
+    let content = fs::read_to_string(spec_path)
+        .with_context(|| format!("Could not open spec [{:?}]", &spec_path))?;
+    let mut spec_as_json: Value = serde_yaml::from_str(&content)
+        .with_context(|| format!("Could not parse spec as yaml [{:?}]", &spec_path))?;
+    let source = (!CLI.overlays.is_empty() || swagger::is_swagger_spec(&spec_as_json))
+        .then(|| spec_as_json.clone());
+
+    overlay::apply_overlays(&mut spec_as_json, &CLI.overlays)
+        .with_context(|| format!("Could not apply overlays to spec [{:?}]", &spec_path))?;
+    if swagger::is_swagger_spec(&spec_as_json) {
+        spec_as_json = swagger::upgrade_spec(&spec_as_json)
+            .with_context(|| format!("Could not upgrade Swagger 2.0 spec [{:?}]", &spec_path))?;
+    }
+
+    let spec_uri = get_spec_uri(spec_path)?;
+    let mut locations = get_locations(spec_uri.as_str(), &content);
+    if let Some(source) = source.as_ref() {
+        locations = locations::retain_sourced(locations, source, &spec_as_json);
+    }
+    Ok((spec_as_json, locations))
+}
+
+pub fn get_spec_uri(spec_path: &Path) -> Result<Url> {
+    let absolute_path = fs::canonicalize(spec_path)
+        .with_context(|| format!("Could not canonicalize spec path [{:?}]", spec_path))?;
+    Url::from_file_path(&absolute_path)
+        .map_err(|_| anyhow!("Could not convert spec path [{:?}] to URI", &absolute_path))
+}
+
+/// Nodes absent in source locations are located by nearest parent
+pub fn new_parsed_spec(
+    spec_path: &Path,
+    spec_as_json: Value,
+    locations: HashMap<String, SourcePosition>,
+) -> Result<ParsedSpec> {
+    let mut parsed_spec = ParsedSpec::new(spec_path.to_owned(), spec_as_json)?;
+
+    // Only references inside schemas with `$id` have other base than root spec
+    let base = parsed_spec.uri.clone();
+    let spec = Arc::get_mut(&mut parsed_spec.spec).expect("Spec is shared before load end");
+    rebase_references(spec, &base, false)?;
+
+    let root_uri = absolute_reference("", &parsed_spec)?;
+    parsed_spec.add_locations(split_reference(&root_uri).0, locations);
+    Ok(parsed_spec)
+}
+
+pub fn resolve_reference<T>(uri: &str, parsed_spec: &ParsedSpec) -> Result<Arc<ReferenceOr<T>>>
 where
-    T: DeserializeOwned + Send + Sync,
+    T: DeserializeOwned + Send + Sync + 'static,
 {
-    let cached_value = compute_if_absent(String::from(uri), move || {
-        let clean_uri = uri.split_once('#').map(|x| x.0).unwrap_or("/");
-
-        let cached_schema = compute_if_absent(String::from(clean_uri), move || {
-            Ok(match &uri {
-                u if u.starts_with("#/") => parsed_spec.spec.clone(),
-                u if u.starts_with("http://") || u.starts_with("https://") => Arc::new(
-                    serde_yaml::from_reader(fetch_url_content(uri)?)
-                        .with_context(|| format!("Could not parse yaml url content [{}]", &uri))?,
-                ),
-                _ => Arc::new(
-                    serde_yaml::from_reader(fetch_file_content(&parsed_spec.path, uri)?)
-                        .with_context(|| format!("Could not parse yaml file content [{}]", &uri))?,
-                ),
-            })
-        })?;
+    let chain = resolve_reference_chain(uri, parsed_spec)?;
+    let target_uri = chain
+        .last()
+        .ok_or_else(|| anyhow!("Empty chain of references for [{}]", uri))?;
 
-        let target = cached_schema
-            .pointer(&extract_json_pointer(uri))
-            .ok_or(anyhow!("Pointer target [{}] not found in content", uri))?;
+    let cached_value = parsed_spec
+        .store
+        .compute_if_absent(target_uri.clone(), || {
+            let mut target = resolve_value(target_uri, parsed_spec)?;
+            if json_schema::is_json_schema_dialect(&parsed_spec.spec, parsed_spec.input_kind) {
+                if TypeId::of::<T>() == TypeId::of::<Schema>() {
+                    json_schema::normalize_schema(&mut target);
+                } else {
+                    json_schema::normalize_document(&mut target);
+                }
+            }
 
-        Ok(ReferenceOr::Item(
-            serde_json::from_value::<T>(target.clone())
-                .with_context(|| format!("Could not produce target model [{}]", &uri))?,
-        ))
-    })?;
+            Ok(ReferenceOr::Item(
+                serde_json::from_value::<T>(target)
+                    .with_context(|| format!("Could not produce target model [{}]", &uri))?,
+            ))
+        })?;
 
     Ok(cached_value)
 }
 
-fn extract_json_pointer(uri: &str) -> String {
-    let pointer = String::from(uri.split_once('#').map(|x| x.1).unwrap_or("/"));
+/// Follows reference through targets which are references too, returns absolute references from given one
+/// to one with concrete item as target
+pub fn resolve_reference_chain(uri: &str, parsed_spec: &ParsedSpec) -> Result<Arc<Vec<String>>> {
+    let absolute_uri = absolute_reference(uri, parsed_spec)?;
+
+    let key = format!("{}{}", CHAIN_KEY_PREFIX, absolute_uri);
+    parsed_spec.store.compute_if_absent(key, || {
+        let mut chain = vec![absolute_uri];
+        loop {
+            let current = chain.last().expect("Chain of references is empty");
+            let target = resolve_value(current, parsed_spec)?;
+            let Some(next) = target.get(REFERENCE_KEY).and_then(Value::as_str) else {
+                return Ok(chain);
+            };
+
+            let next_absolute = absolute_reference(next, parsed_spec)?;
+            debug!(
+                "Reference [{}] points to reference [{}]",
+                current, next_absolute
+            );
+            if chain.contains(&next_absolute) {
+                return Err(anyhow!(
+                    "Loop of references [{} -> {}]",
+                    chain.join(" -> "),
+                    next_absolute
+                ));
+            }
+            chain.push(next_absolute);
+        }
+    })
+}
+
+/// Resolves reference to raw content of target, references in content of other documents are absolute.
+/// Document part of reference can be `$id` of schema and fragment can be `$anchor` name (JSON Schema 2020-12)
+pub fn resolve_value(uri: &str, parsed_spec: &ParsedSpec) -> Result<Value> {
+    let absolute_uri = absolute_reference(uri, parsed_spec)?;
+    let (document_uri, fragment) = split_reference(&absolute_uri);
+
+    // Identifiers of root spec can be used before any reference to root spec itself
+    get_document(&absolute_reference("", parsed_spec)?, parsed_spec)?;
+
+    let (location_document, location) = match parsed_spec.find_identifier(document_uri) {
+        Some(identified) => identified,
+        None => (document_uri.to_owned(), Vec::new()),
+    };
+    let document = get_document(&location_document, parsed_spec)?;
+
+    if !fragment.is_empty() && !fragment.starts_with('/') {
+        let (anchor_document, anchor_location) = parsed_spec
+            .find_identifier(&absolute_uri)
+            .ok_or_else(|| anyhow!("Anchor [{}] not found in [{}]", fragment, document_uri))?;
+        let document = get_document(&anchor_document, parsed_spec)?;
+        return get_location(&document, anchor_location, &anchor_document).cloned();
+    }
+
+    let identified = get_location(&document, location, &location_document)?;
+    get_pointer_target(identified, &absolute_uri).cloned()
+}
+
+fn get_document(document_uri: &str, parsed_spec: &ParsedSpec) -> Result<Arc<Value>> {
+    let document = parsed_spec
+        .store
+        .compute_if_absent(document_uri.to_owned(), || {
+            load_document(document_uri, parsed_spec)
+        })?;
+    Ok(Arc::clone(&document))
+}
+
+fn get_location<'a>(
+    document: &'a Value,
+    location: Vec<String>,
+    document_uri: &str,
+) -> Result<&'a Value> {
+    let pointer = JsonPointer::new(location);
+    pointer.get(document).map_err(|_| {
+        anyhow!(
+            "Identified schema [{}] not found in [{}]",
+            pointer,
+            document_uri
+        )
+    })
+}
+
+/// Mapping value like `Pet.yaml` is a reference if it resolves, otherwise it is a name of schema in components
+/// like `com.acme.Pet`
+pub fn get_discriminator_reference(target: &str, parsed_spec: &ParsedSpec) -> String {
+    if spec_keys::is_discriminator_reference(target)
+        || (target.contains('.') && resolve_value(target, parsed_spec).is_ok())
+    {
+        target.to_owned()
+    } else {
+        spec_keys::get_schema_reference(target)
+    }
+}
+
+/// Resolves reference from root spec to absolute form `document#fragment` (RFC 3986), references from other
+/// documents are already absolute after loading
+pub fn absolute_reference(uri: &str, parsed_spec: &ParsedSpec) -> Result<String> {
+    resolve_against(&parsed_spec.uri, uri)
+}
+
+pub fn resolve_against(base: &Url, uri: &str) -> Result<String> {
+    let (document, fragment) = split_reference(uri);
+    let document_url = if document.is_empty() {
+        let mut it = base.clone();
+        it.set_fragment(None);
+        it
+    } else {
+        base.join(document)
+            .with_context(|| format!("Could not resolve [{}] against base [{}]", uri, base))?
+    };
+    let mut document_uri = document_url.to_string();
+    if let Some(stripped) = document_uri.strip_suffix('#') {
+        document_uri = stripped.to_owned();
+    }
+    Ok(format!("{}#{}", document_uri, fragment))
+}
+
+pub fn split_reference(uri: &str) -> (&str, &str) {
+    uri.split_once('#').unwrap_or((uri, ""))
+}
+
+/// Parses fragment as JSON pointer in URI fragment representation (RFC 6901 section 6), percent-encoded
+/// octets are decoded as UTF-8 (RFC 3986) before `~` escapes, empty fragment points to whole document
+pub fn parse_json_pointer(fragment: &str) -> Result<JsonPointer<String, Vec<String>>> {
+    let decoded = percent_decode_str(fragment)
+        .decode_utf8()
+        .with_context(|| format!("Fragment [{}] is not valid percent-encoded UTF-8", fragment))?;
+    // Leading '#' would be taken by parser as one more fragment to decode
+    if !decoded.is_empty() && !decoded.starts_with('/') {
+        return Err(anyhow!(
+            "Fragment [{}] is not JSON pointer, it must be empty or start with '/'",
+            fragment
+        ));
+    }
+    decoded.parse().map_err(|error| match error {
+        ParseError::InvalidEscape(escape) => anyhow!(
+            "Invalid escape [{}] in JSON pointer [{}], only '~0' and '~1' allowed",
+            escape,
+            fragment
+        ),
+        ParseError::NoLeadingSlash => anyhow!("JSON pointer [{}] has no leading '/'", fragment),
+    })
+}
+
+fn get_pointer_target<'a>(document: &'a Value, uri: &str) -> Result<&'a Value> {
+    let (_, fragment) = split_reference(uri);
+    let pointer = parse_json_pointer(fragment)?;
     debug!("Resolved json pointer [{}]", pointer);
-    pointer
-}
-
-fn fetch_url_content(uri: &str) -> Result<Box<dyn Read>> {
-    let mut url = Url::parse(uri).with_context(|| format!("Could not parse URI [{}]", &uri))?;
-    url.set_fragment(None);
-    debug!("Resolved external $ref [{}]", url);
-    let response = CLIENT
-        .get(url)
-        .send()
-        .with_context(|| format!("Remote request to [{}] error", &uri))?;
-    if response.status().is_success() {
-        Ok(Box::new(response))
+
+    pointer.get(document).map_err(|error| {
+        // Shortest prefix that could not be resolved ends with failing segment
+        let mut resolved = pointer.clone();
+        let mut segment = None;
+        while resolved.get(document).is_err() {
+            segment = resolved.pop();
+        }
+        let segment = segment.unwrap_or_default();
+        let reason = match error {
+            IndexError::NoSuchKey(_) => "no such key".to_owned(),
+            IndexError::OutOfBounds(index) => format!("index [{}] out of bounds", index),
+            IndexError::NotIndexable => "target is neither object nor array".to_owned(),
+        };
+        anyhow!(
+            "Segment [{}] after [{}] of pointer [{}] not found in [{}]: {}",
+            segment,
+            resolved,
+            fragment,
+            split_reference(uri).0,
+            reason
+        )
+    })
+}
+
+fn load_document(document_uri: &str, parsed_spec: &ParsedSpec) -> Result<Arc<Value>> {
+    let document_url = Url::parse(document_uri)
+        .with_context(|| format!("Could not parse URI [{}]", &document_uri))?;
+
+    let mut root_uri = parsed_spec.uri.clone();
+    root_uri.set_fragment(None);
+    let document = if root_uri.as_str() == document_uri {
+        parsed_spec.spec.clone()
     } else {
-        Err(anyhow!("Failed to fetch the content by uri {}", uri))
+        let url = map_reference_url(document_url.clone())?;
+        let mut content = String::new();
+        match url.scheme() {
+            "http" | "https" => fetch_remote_content(&url)?,
+            "file" => fetch_file_content(&url)?,
+            scheme => return Err(anyhow!("Unsupported URI scheme [{}] in [{}]", scheme, url)),
+        }
+        .read_to_string(&mut content)
+        .with_context(|| format!("Could not read content [{}]", &url))?;
+
+        let mut document: Value = serde_yaml::from_str(&content)
+            .with_context(|| format!("Could not parse yaml content [{}]", &url))?;
+        let mut locations = get_locations(document_uri, &content);
+        if swagger::is_swagger_spec(&document) {
+            let upgraded = swagger::upgrade_document(&document)
+                .with_context(|| format!("Could not upgrade Swagger 2.0 document [{}]", &url))?;
+            locations = locations::retain_sourced(locations, &document, &upgraded);
+            document = upgraded;
+        }
+        parsed_spec.add_locations(document_uri, locations);
+        rebase_references(&mut document, &url, true)?;
+        Arc::new(document)
+    };
+
+    let mut identifiers = HashMap::new();
+    collect_identifiers(
+        &document,
+        &document_url,
+        document_uri,
+        &mut Vec::new(),
+        false,
+        &mut identifiers,
+    )?;
+    parsed_spec.add_identifiers(identifiers);
+    Ok(document)
+}
+
+/// Locations are used only to describe errors, so document without them is still usable
+fn get_locations(document_uri: &str, content: &str) -> HashMap<String, SourcePosition> {
+    let (document_uri, _) = split_reference(document_uri);
+    locations::index_locations(document_uri, content).unwrap_or_else(|error| {
+        warn!("No source locations for [{}]: {:#}", document_uri, error);
+        HashMap::new()
+    })
+}
+
+/// References relative to document or to `$id` of schema around them are replaced by absolute references,
+/// in root spec only ones inside schema with `$id`
+pub fn rebase_references(value: &mut Value, base: &Url, rebase_all: bool) -> Result<()> {
+    rebase_node(value, base, rebase_all, false)
+}
+
+/// Keys of named map are names, so `default` or `$id` property is walked as any other
+fn rebase_node(value: &mut Value, base: &Url, rebase_all: bool, in_named_map: bool) -> Result<()> {
+    match value {
+        Value::Object(map) => {
+            let (base, rebase_all) = match map.get(ID_KEY).filter(|_| !in_named_map) {
+                Some(Value::String(id)) => (
+                    &base.join(id).with_context(|| {
+                        format!("Could not resolve [{}] against base [{}]", id, base)
+                    })?,
+                    true,
+                ),
+                _ => (base, rebase_all),
+            };
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::String(reference) if key == REFERENCE_KEY && !in_named_map => {
+                        if rebase_all {
+                            *reference = resolve_against(base, reference)?;
+                        }
+                    }
+                    _ if spec_keys::is_literal(key, child, in_named_map) => {}
+                    _ => rebase_node(
+                        child,
+                        base,
+                        rebase_all,
+                        spec_keys::is_named_map(key, in_named_map),
+                    )?,
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                rebase_node(item, base, rebase_all, false)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Collects `$id` and `$anchor` of schemas in document with locations of these schemas
+fn collect_identifiers(
+    value: &Value,
+    base: &Url,
+    document_uri: &str,
+    location: &mut Vec<String>,
+    in_named_map: bool,
+    identifiers: &mut HashMap<String, (String, Vec<String>)>,
+) -> Result<()> {
+    match value {
+        Value::Object(map) if in_named_map => {
+            for (key, child) in map {
+                location.push(key.clone());
+                collect_identifiers(child, base, document_uri, location, false, identifiers)?;
+                location.pop();
+            }
+        }
+        Value::Object(map) => {
+            let mut base = base.clone();
+            if let Some(Value::String(id)) = map.get(ID_KEY) {
+                base = base.join(id).with_context(|| {
+                    format!("Could not resolve [{}] against base [{}]", id, base)
+                })?;
+                base.set_fragment(None);
+                debug!("Found schema [{}] in [{}]", base, document_uri);
+                identifiers.insert(
+                    base.as_str().to_owned(),
+                    (document_uri.to_owned(), location.clone()),
+                );
+            }
+            for anchor in ANCHOR_KEYS.iter().filter_map(|it| map.get(*it)) {
+                if let Value::String(anchor) = anchor {
+                    identifiers.insert(
+                        format!("{}#{}", base, anchor),
+                        (document_uri.to_owned(), location.clone()),
+                    );
+                }
+            }
+            for (key, child) in map
+                .iter()
+                .filter(|(key, child)| !spec_keys::is_literal(key, child, false))
+            {
+                location.push(key.clone());
+                collect_identifiers(
+                    child,
+                    &base,
+                    document_uri,
+                    location,
+                    spec_keys::is_named_map(key, false),
+                    identifiers,
+                )?;
+                location.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                location.push(index.to_string());
+                collect_identifiers(item, base, document_uri, location, false, identifiers)?;
+                location.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Replaces remote URL prefix to local path by --ref-map rules
+fn map_reference_url(url: Url) -> Result<Url> {
+    match CLI
+        .ref_map
+        .iter()
+        .find(|(prefix, _)| url.as_str().starts_with(prefix.as_str()))
+    {
+        Some((prefix, local_path)) => {
+            let mapped_path = path::absolute(local_path.join(&url.as_str()[prefix.len()..]))
+                .with_context(|| format!("Could not map [{}] to [{:?}]", url, local_path))?;
+            debug!("Mapped $ref [{}] to [{:?}]", url, mapped_path);
+            Url::from_file_path(&mapped_path)
+                .map_err(|_| anyhow!("Could not convert path [{:?}] to URI", &mapped_path))
+        }
+        None => Ok(url),
+    }
+}
+
+/// Remote document is taken from cache dir if it present, otherwise fetched and saved to cache dir
+fn fetch_remote_content(url: &Url) -> Result<Box<dyn Read>> {
+    let cached_path = CLI
+        .refs_cache_path
+        .as_ref()
+        .map(|it| it.join(get_cache_file_name(url)));
+
+    if let Some(path) = cached_path.as_ref().filter(|it| it.is_file()) {
+        debug!("Used cached remote document [{:?}] for [{}]", path, url);
+        return Ok(Box::new(File::open(path).with_context(|| {
+            format!("Could not open cached document [{:?}]", path)
+        })?));
+    }
+
+    if CLI.offline {
+        return Err(anyhow!(
+            "Remote reference [{}] is not allowed in offline mode, map it to local path with --ref-map",
+            url
+        ));
+    }
+
+    let mut content = Vec::new();
+    http::fetch_url_content(url)?
+        .read_to_end(&mut content)
+        .with_context(|| format!("Could not read remote content [{}]", url))?;
+
+    // Document is already fetched, so translation goes on without cache
+    if let Some(path) = cached_path {
+        let saved = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, &content));
+        match saved {
+            Ok(_) => debug!("Saved remote document [{}] to cache [{:?}]", url, path),
+            Err(error) => warn!(
+                "Could not save remote document [{}] to cache [{:?}]: {}",
+                url, path, error
+            ),
+        }
     }
+
+    Ok(Box::new(Cursor::new(content)))
+}
+
+/// Hash of whole URL keeps different URLs apart, last path segment is added to find file by eye
+fn get_cache_file_name(url: &Url) -> String {
+    // FNV-1a is stable across builds unlike hasher of std
+    let hash = url
+        .as_str()
+        .bytes()
+        .fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    let segment = url
+        .path_segments()
+        .and_then(|mut it| it.next_back())
+        .unwrap_or_default();
+    let suffix: String = percent_decode_str(segment)
+        .decode_utf8_lossy()
+        .chars()
+        .map(|it| match it {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '.' | '_' => it,
+            _ => '_',
+        })
+        .take(CACHE_FILE_NAME_SUFFIX_MAX_LEN)
+        .collect();
+    format!("{:016x}-{}", hash, suffix)
 }
 
-fn fetch_file_content(base_spec_path: &Path, uri: &str) -> Result<Box<dyn Read>> {
-    let unparsed_path: Vec<_> = uri.split('#').collect();
-    let ref_spec_path = base_spec_path.with_file_name(
-        unparsed_path
-            .first()
-            .ok_or_else(|| anyhow!("Could not parse file path {}", uri))?,
-    );
+fn fetch_file_content(url: &Url) -> Result<Box<dyn Read>> {
+    let ref_spec_path = url
+        .to_file_path()
+        .map_err(|_| anyhow!("Could not convert URI [{}] to file path", url))?;
 
-    debug!(
-        "Base spec path [{:?}]\nResolved $ref path [{:?}]",
-        fs::canonicalize(base_spec_path),
-        fs::canonicalize(&ref_spec_path)
-    );
+    debug!("Resolved $ref path [{:?}]", &ref_spec_path);
 
     Ok(Box::new(File::open(&ref_spec_path).with_context(|| {
         format!("Could not open file [{:?}]", &ref_spec_path)
@@ -3,7 +3,7 @@ use mlua::{Function, Lua};
 
 use crate::enums::common::Script;
 
-pub fn get_call_id(schema_name: Option<&str>, reference: &String) -> Option<String> {
+pub fn get_call_id(schema_name: Option<&str>, reference: &str) -> Option<String> {
     Some(schema_name.map_or_else(
         || reference.to_owned(),
         |name| format!("{}->{}", name, reference),
@@ -0,0 +1,98 @@
+use serde_json::Value;
+
+pub const REFERENCE_KEY: &str = "$ref";
+pub const PATHS_KEY: &str = "paths";
+pub const WEBHOOKS_KEY: &str = "webhooks";
+pub const COMPONENTS_KEY: &str = "components";
+pub const SCHEMAS_KEY: &str = "schemas";
+pub const PARAMETERS_KEY: &str = "parameters";
+pub const ALL_OF_KEY: &str = "allOf";
+pub const OPERATION_KEYS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+/// Values of these keys are instance data, unless they are names in a map of named objects
+pub const LITERAL_KEYS: [&str; 6] = ["example", "examples", "default", "enum", "const", "value"];
+/// Keys of maps where keys are names chosen by spec author
+pub const NAMED_MAPS_KEYS: [&str; 21] = [
+    "properties",
+    "patternProperties",
+    "$defs",
+    "definitions",
+    "dependentSchemas",
+    "paths",
+    "webhooks",
+    "callbacks",
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "pathItems",
+    "content",
+    "encoding",
+    "mapping",
+    "variables",
+];
+/// Keys of schema with single subschema as value
+pub const SUBSCHEMA_KEYS: [&str; 12] = [
+    "items",
+    "additionalItems",
+    "additionalProperties",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "contains",
+    "propertyNames",
+    "contentSchema",
+    "not",
+    "if",
+    "then",
+    "else",
+];
+/// Keys of schema with list of subschemas as value
+pub const SUBSCHEMA_LISTS_KEYS: [&str; 4] = ["allOf", "anyOf", "oneOf", "prefixItems"];
+/// Keys of schema with map of subschemas as value
+pub const SUBSCHEMA_MAPS_KEYS: [&str; 5] = [
+    "properties",
+    "patternProperties",
+    "$defs",
+    "definitions",
+    "dependentSchemas",
+];
+
+/// Extensions and instance data are not parts of spec. `examples` of schema is a list of instances,
+/// but `examples` of media type, parameter or components is a map of Example objects
+pub fn is_literal(key: &str, value: &Value, in_named_map: bool) -> bool {
+    !in_named_map
+        && (key.starts_with("x-")
+            || (LITERAL_KEYS.contains(&key) && !(key == "examples" && value.is_object())))
+}
+
+/// Keys of named map are names, so its children are never named maps themselves
+pub fn is_named_map(key: &str, in_named_map: bool) -> bool {
+    !in_named_map && NAMED_MAPS_KEYS.contains(&key)
+}
+
+/// Escapes key as segment of JSON pointer (RFC 6901)
+pub fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+pub fn unescape(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+pub fn child_location(location: &str, key: &str) -> String {
+    format!("{}/{}", location, escape(key))
+}
+
+pub fn get_schema_reference(name: &str) -> String {
+    format!("#/{}/{}/{}", COMPONENTS_KEY, SCHEMAS_KEY, escape(name))
+}
+
+/// Mapping value with path or fragment is a reference, value with dots only can be a file or a schema name
+pub fn is_discriminator_reference(target: &str) -> bool {
+    target.contains(['/', '#'])
+}
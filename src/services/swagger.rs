@@ -0,0 +1,762 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde_json::{json, Map, Value};
+
+use crate::services::spec_keys::{escape, unescape, OPERATION_KEYS, REFERENCE_KEY};
+
+const OPENAPI_VERSION: &str = "3.0.3";
+const DEFAULT_MEDIA_TYPE: &str = "application/json";
+const FORM_MEDIA_TYPE: &str = "application/x-www-form-urlencoded";
+const MULTIPART_MEDIA_TYPE: &str = "multipart/form-data";
+/// Keys of Swagger 2.0 parameter which are schema of parameter in OpenAPI 3.0
+const PARAMETER_SCHEMA_KEYS: [&str; 16] = [
+    "type",
+    "format",
+    "items",
+    "default",
+    "maximum",
+    "exclusiveMaximum",
+    "minimum",
+    "exclusiveMinimum",
+    "maxLength",
+    "minLength",
+    "pattern",
+    "maxItems",
+    "minItems",
+    "uniqueItems",
+    "enum",
+    "multipleOf",
+];
+/// Keys of spec and operation copied to OpenAPI 3.0 as is
+const COMMON_KEYS: [&str; 9] = [
+    "info",
+    "tags",
+    "externalDocs",
+    "security",
+    "summary",
+    "description",
+    "operationId",
+    "deprecated",
+    "$ref",
+];
+
+/// Swagger 2.0 spec has `swagger` field instead of `openapi`
+pub fn is_swagger_spec(spec: &Value) -> bool {
+    spec.get("swagger").and_then(Value::as_str) == Some("2.0")
+}
+
+/// Converts Swagger 2.0 spec to OpenAPI 3.0, parts without equivalent are logged as warnings
+pub fn upgrade_spec(spec: &Value) -> Result<Value> {
+    let swagger = spec
+        .as_object()
+        .ok_or_else(|| anyhow!("Swagger spec is not an object"))?;
+    info!("Upgrade Swagger 2.0 spec to OpenAPI {}", OPENAPI_VERSION);
+
+    let upgrader = Upgrader {
+        swagger,
+        consumes: get_strings(swagger.get("consumes")),
+        produces: get_strings(swagger.get("produces")),
+    };
+
+    let mut openapi = Map::new();
+    openapi.insert(
+        "openapi".to_owned(),
+        Value::String(OPENAPI_VERSION.to_owned()),
+    );
+    copy_common(swagger, &mut openapi);
+    let servers = upgrader.get_servers(&get_strings(swagger.get("schemes")));
+    openapi.insert("servers".to_owned(), Value::Array(servers));
+    openapi.insert("paths".to_owned(), Value::Object(upgrader.get_paths()?));
+
+    let components = upgrader.get_components()?;
+    if !components.is_empty() {
+        openapi.insert("components".to_owned(), Value::Object(components));
+    }
+
+    let mut openapi = Value::Object(openapi);
+    rewrite_references(&mut openapi, swagger);
+    Ok(openapi)
+}
+
+/// Swagger 2.0 document referenced from spec is upgraded too, Swagger 2.0 pointers of its components
+/// are kept as references to them, so references like `common.yaml#/definitions/Error` are still resolved
+pub fn upgrade_document(document: &Value) -> Result<Value> {
+    let mut upgraded = upgrade_spec(document)?;
+    let components = upgraded.get("components").cloned().unwrap_or_default();
+    let Value::Object(upgraded_map) = &mut upgraded else {
+        return Err(anyhow!("Upgraded document is not an object"));
+    };
+
+    for (swagger_key, kinds) in [
+        ("definitions", ["schemas"].as_slice()),
+        ("parameters", ["parameters", "requestBodies"].as_slice()),
+        ("responses", ["responses"].as_slice()),
+    ] {
+        let aliases: Map<String, Value> = kinds
+            .iter()
+            .filter_map(|kind| {
+                components
+                    .get(kind)
+                    .and_then(Value::as_object)
+                    .map(|it| (kind, it))
+            })
+            .flat_map(|(kind, named)| {
+                named.keys().map(move |name| {
+                    let reference = format!("#/components/{}/{}", kind, escape(name));
+                    (name.clone(), json!({ REFERENCE_KEY: reference }))
+                })
+            })
+            .collect();
+        if !aliases.is_empty() {
+            upgraded_map.insert(swagger_key.to_owned(), Value::Object(aliases));
+        }
+    }
+    Ok(upgraded)
+}
+
+struct Upgrader<'a> {
+    swagger: &'a Map<String, Value>,
+    consumes: Vec<String>,
+    produces: Vec<String>,
+}
+
+impl Upgrader<'_> {
+    /// Server for every scheme with host and base path, relative server without host
+    fn get_servers(&self, schemes: &[String]) -> Vec<Value> {
+        let base_path = self
+            .swagger
+            .get("basePath")
+            .and_then(Value::as_str)
+            .unwrap_or("/");
+
+        let urls: Vec<String> = match self.swagger.get("host").and_then(Value::as_str) {
+            Some(host) => {
+                let default_schemes = ["https".to_owned()];
+                let schemes = if schemes.is_empty() {
+                    default_schemes.as_slice()
+                } else {
+                    schemes
+                };
+                schemes
+                    .iter()
+                    .map(|scheme| {
+                        format!("{}://{}{}", scheme, host, base_path.trim_end_matches('/'))
+                    })
+                    .collect()
+            }
+            None => vec![base_path.to_owned()],
+        };
+
+        urls.into_iter()
+            .map(|url| {
+                let mut server = Map::new();
+                server.insert("url".to_owned(), Value::String(url));
+                Value::Object(server)
+            })
+            .collect()
+    }
+
+    fn get_paths(&self) -> Result<Map<String, Value>> {
+        let mut paths = Map::new();
+        let Some(Value::Object(swagger_paths)) = self.swagger.get("paths") else {
+            return Ok(paths);
+        };
+
+        for (path, swagger_path_item) in swagger_paths {
+            let Value::Object(swagger_path_item) = swagger_path_item else {
+                continue;
+            };
+            let mut path_item = Map::new();
+            copy_common(swagger_path_item, &mut path_item);
+
+            let path_parameters = get_array(swagger_path_item.get("parameters"));
+            let (parameters, path_body) = self.split_parameters(path_parameters, path)?;
+            if !parameters.is_empty() {
+                path_item.insert("parameters".to_owned(), Value::Array(parameters));
+            }
+
+            for method in OPERATION_KEYS {
+                if let Some(Value::Object(swagger_operation)) = swagger_path_item.get(method) {
+                    let operation = self.get_operation(
+                        swagger_operation,
+                        path_body.as_ref(),
+                        &format!("{} {}", method, path),
+                    )?;
+                    path_item.insert(method.to_owned(), Value::Object(operation));
+                }
+            }
+            paths.insert(path.clone(), Value::Object(path_item));
+        }
+        Ok(paths)
+    }
+
+    fn get_operation(
+        &self,
+        swagger_operation: &Map<String, Value>,
+        path_body: Option<&Body>,
+        operation_name: &str,
+    ) -> Result<Map<String, Value>> {
+        let mut operation = Map::new();
+        copy_common(swagger_operation, &mut operation);
+
+        let consumes = get_strings(swagger_operation.get("consumes"));
+        let consumes = if consumes.is_empty() {
+            &self.consumes
+        } else {
+            &consumes
+        };
+        let produces = get_strings(swagger_operation.get("produces"));
+        let produces = if produces.is_empty() {
+            &self.produces
+        } else {
+            &produces
+        };
+
+        let schemes = get_strings(swagger_operation.get("schemes"));
+        if !schemes.is_empty() && schemes != get_strings(self.swagger.get("schemes")) {
+            if self.swagger.contains_key("host") {
+                operation.insert(
+                    "servers".to_owned(),
+                    Value::Array(self.get_servers(&schemes)),
+                );
+            } else {
+                warn!(
+                    "Schemes of operation [{}] are ignored, spec has no host",
+                    operation_name
+                );
+            }
+        }
+
+        let swagger_parameters = get_array(swagger_operation.get("parameters"));
+        let (parameters, body) = self.split_parameters(swagger_parameters, operation_name)?;
+        if !parameters.is_empty() {
+            operation.insert("parameters".to_owned(), Value::Array(parameters));
+        }
+        if let Some(body) = body.as_ref().or(path_body) {
+            operation.insert("requestBody".to_owned(), body.to_request_body(consumes));
+        }
+
+        if let Some(Value::Object(swagger_responses)) = swagger_operation.get("responses") {
+            let responses: Map<String, Value> = swagger_responses
+                .iter()
+                .map(|(code, response)| {
+                    let converted = if is_extension(code) {
+                        response.clone()
+                    } else {
+                        upgrade_response(response, produces)
+                    };
+                    (code.clone(), converted)
+                })
+                .collect();
+            operation.insert("responses".to_owned(), Value::Object(responses));
+        }
+        Ok(operation)
+    }
+
+    /// Body and form parameters become request body, other ones are upgraded
+    fn split_parameters(
+        &self,
+        swagger_parameters: &[Value],
+        place: &str,
+    ) -> Result<(Vec<Value>, Option<Body>)> {
+        let mut parameters = Vec::new();
+        let mut body: Option<Body> = None;
+        let mut form = Map::new();
+        let mut form_required = Vec::new();
+
+        for swagger_parameter in swagger_parameters {
+            let (parameter, reference) = match swagger_parameter.get(REFERENCE_KEY) {
+                Some(Value::String(reference)) => {
+                    (self.get_global_parameter(reference)?, Some(reference))
+                }
+                _ => (swagger_parameter, None),
+            };
+
+            match parameter.get("in").and_then(Value::as_str) {
+                Some("body") => {
+                    if body.is_some() {
+                        warn!("Only first body parameter of [{}] is kept", place);
+                        continue;
+                    }
+                    body = Some(match reference {
+                        Some(reference) => {
+                            Body::Reference(get_parameter_name(reference).to_owned())
+                        }
+                        None => Body::Inline(parameter.clone()),
+                    });
+                }
+                Some("formData") => {
+                    let name = parameter
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| anyhow!("Form parameter of [{}] has no name", place))?;
+                    if parameter.get("required").and_then(Value::as_bool) == Some(true) {
+                        form_required.push(Value::String(name.to_owned()));
+                    }
+                    form.insert(name.to_owned(), get_parameter_schema(parameter));
+                }
+                _ => parameters.push(match reference {
+                    Some(_) => swagger_parameter.clone(),
+                    None => upgrade_parameter(parameter),
+                }),
+            }
+        }
+
+        if !form.is_empty() {
+            if body.is_some() {
+                warn!(
+                    "Form parameters of [{}] are dropped, it has body parameter",
+                    place
+                );
+            } else {
+                body = Some(Body::Form(form, form_required));
+            }
+        }
+        Ok((parameters, body))
+    }
+
+    fn get_global_parameter(&self, reference: &str) -> Result<&Value> {
+        match reference.strip_prefix("#/parameters/") {
+            Some(name) => self
+                .swagger
+                .get("parameters")
+                .and_then(|it| it.get(unescape(name)))
+                .ok_or_else(|| anyhow!("Parameter [{}] not found", reference)),
+            // Parameters in other documents could not be checked for body
+            None => Ok(&Value::Null),
+        }
+    }
+
+    fn get_components(&self) -> Result<Map<String, Value>> {
+        let mut components = Map::new();
+
+        if let Some(Value::Object(definitions)) = self.swagger.get("definitions") {
+            let schemas = definitions
+                .iter()
+                .map(|(name, schema)| (name.clone(), upgrade_schema(schema)))
+                .collect();
+            components.insert("schemas".to_owned(), Value::Object(schemas));
+        }
+
+        if let Some(Value::Object(swagger_parameters)) = self.swagger.get("parameters") {
+            let mut parameters = Map::new();
+            let mut request_bodies = Map::new();
+            for (name, parameter) in swagger_parameters {
+                match parameter.get("in").and_then(Value::as_str) {
+                    Some("body") => {
+                        let body = Body::Inline(parameter.clone());
+                        request_bodies.insert(name.clone(), body.to_request_body(&self.consumes));
+                    }
+                    Some("formData") => {
+                        warn!("Form parameter [{}] is inlined to operations only", name);
+                    }
+                    _ => {
+                        parameters.insert(name.clone(), upgrade_parameter(parameter));
+                    }
+                }
+            }
+            if !parameters.is_empty() {
+                components.insert("parameters".to_owned(), Value::Object(parameters));
+            }
+            if !request_bodies.is_empty() {
+                components.insert("requestBodies".to_owned(), Value::Object(request_bodies));
+            }
+        }
+
+        if let Some(Value::Object(swagger_responses)) = self.swagger.get("responses") {
+            let responses = swagger_responses
+                .iter()
+                .map(|(name, response)| (name.clone(), upgrade_response(response, &self.produces)))
+                .collect();
+            components.insert("responses".to_owned(), Value::Object(responses));
+        }
+
+        if let Some(Value::Object(definitions)) = self.swagger.get("securityDefinitions") {
+            let schemes = definitions
+                .iter()
+                .map(|(name, scheme)| (name.clone(), upgrade_security_scheme(name, scheme)))
+                .collect();
+            components.insert("securitySchemes".to_owned(), Value::Object(schemes));
+        }
+        Ok(components)
+    }
+}
+
+enum Body {
+    Reference(String),
+    Inline(Value),
+    Form(Map<String, Value>, Vec<Value>),
+}
+
+impl Body {
+    fn to_request_body(&self, consumes: &[String]) -> Value {
+        let mut request_body = Map::new();
+        match self {
+            Body::Reference(name) => {
+                request_body.insert(
+                    REFERENCE_KEY.to_owned(),
+                    Value::String(format!("#/components/requestBodies/{}", name)),
+                );
+            }
+            Body::Inline(parameter) => {
+                copy_keys(parameter, &mut request_body, &["description", "required"]);
+                copy_extensions(parameter, &mut request_body);
+                let schema = parameter
+                    .get("schema")
+                    .map(upgrade_schema)
+                    .unwrap_or_else(|| Value::Object(Map::new()));
+                let media_types = if consumes.is_empty() {
+                    vec![DEFAULT_MEDIA_TYPE.to_owned()]
+                } else {
+                    consumes.to_vec()
+                };
+                request_body.insert(
+                    "content".to_owned(),
+                    get_content(&media_types, &schema, None),
+                );
+            }
+            Body::Form(properties, required) => {
+                let has_file = properties
+                    .values()
+                    .any(|it| it.get("format").and_then(Value::as_str) == Some("binary"));
+                let media_types: Vec<String> = consumes
+                    .iter()
+                    .filter(|it| *it == FORM_MEDIA_TYPE || *it == MULTIPART_MEDIA_TYPE)
+                    .cloned()
+                    .collect();
+                let media_types = match (media_types.is_empty(), has_file) {
+                    (false, _) => media_types,
+                    (true, true) => vec![MULTIPART_MEDIA_TYPE.to_owned()],
+                    (true, false) => vec![FORM_MEDIA_TYPE.to_owned()],
+                };
+
+                let mut schema = Map::new();
+                schema.insert("type".to_owned(), Value::String("object".to_owned()));
+                schema.insert("properties".to_owned(), Value::Object(properties.clone()));
+                if !required.is_empty() {
+                    schema.insert("required".to_owned(), Value::Array(required.clone()));
+                }
+                request_body.insert(
+                    "content".to_owned(),
+                    get_content(&media_types, &Value::Object(schema), None),
+                );
+            }
+        }
+        Value::Object(request_body)
+    }
+}
+
+fn upgrade_parameter(parameter: &Value) -> Value {
+    let Value::Object(swagger_parameter) = parameter else {
+        return parameter.clone();
+    };
+
+    let mut upgraded = Map::new();
+    for (key, value) in swagger_parameter {
+        if !PARAMETER_SCHEMA_KEYS.contains(&key.as_str()) && key != "collectionFormat" {
+            upgraded.insert(key.clone(), value.clone());
+        }
+    }
+    upgraded.insert("schema".to_owned(), get_parameter_schema(parameter));
+
+    let location = swagger_parameter.get("in").and_then(Value::as_str);
+    let format = swagger_parameter
+        .get("collectionFormat")
+        .and_then(Value::as_str);
+    let style = match (format, location) {
+        (None | Some("csv"), Some("query" | "cookie")) if is_array(parameter) => {
+            Some(("form", false))
+        }
+        (Some("multi"), _) => Some(("form", true)),
+        (Some("ssv"), _) => Some(("spaceDelimited", false)),
+        (Some("pipes"), _) => Some(("pipeDelimited", false)),
+        (Some("tsv"), _) => {
+            warn!(
+                "Collection format tsv of parameter [{}] has no equivalent",
+                get_name(parameter)
+            );
+            None
+        }
+        _ => None,
+    };
+    if let Some((style, explode)) = style {
+        upgraded.insert("style".to_owned(), Value::String(style.to_owned()));
+        upgraded.insert("explode".to_owned(), Value::Bool(explode));
+    }
+    Value::Object(upgraded)
+}
+
+/// Schema from type related keys of parameter, header or item
+fn get_parameter_schema(parameter: &Value) -> Value {
+    let mut schema = Map::new();
+    copy_keys(parameter, &mut schema, &PARAMETER_SCHEMA_KEYS);
+    if parameter
+        .get("collectionFormat")
+        .is_some_and(|_| parameter.get("in").is_none())
+    {
+        warn!(
+            "Collection format of [{}] is dropped, there is no style in OpenAPI 3.0 for it",
+            get_name(parameter)
+        );
+    }
+    if let Some(items) = schema.get_mut("items") {
+        *items = get_parameter_schema(items);
+    }
+    upgrade_schema(&Value::Object(schema))
+}
+
+fn upgrade_response(response: &Value, produces: &[String]) -> Value {
+    let Value::Object(swagger_response) = response else {
+        return response.clone();
+    };
+    if swagger_response.contains_key(REFERENCE_KEY) {
+        return response.clone();
+    }
+
+    let mut upgraded = Map::new();
+    copy_keys(response, &mut upgraded, &["description"]);
+    copy_extensions(response, &mut upgraded);
+    if !upgraded.contains_key("description") {
+        upgraded.insert("description".to_owned(), Value::String(String::new()));
+    }
+
+    if let Some(Value::Object(headers)) = swagger_response.get("headers") {
+        let headers = headers
+            .iter()
+            .map(|(name, header)| {
+                let mut upgraded_header = Map::new();
+                copy_keys(header, &mut upgraded_header, &["description"]);
+                copy_extensions(header, &mut upgraded_header);
+                upgraded_header.insert("schema".to_owned(), get_parameter_schema(header));
+                (name.clone(), Value::Object(upgraded_header))
+            })
+            .collect();
+        upgraded.insert("headers".to_owned(), Value::Object(headers));
+    }
+
+    if let Some(schema) = swagger_response.get("schema") {
+        let media_types = if produces.is_empty() {
+            vec![DEFAULT_MEDIA_TYPE.to_owned()]
+        } else {
+            produces.to_vec()
+        };
+        let examples = swagger_response.get("examples").and_then(Value::as_object);
+        if let Some(examples) = examples {
+            for media_type in examples.keys().filter(|it| !media_types.contains(it)) {
+                warn!(
+                    "Example for not produced media type [{}] is dropped",
+                    media_type
+                );
+            }
+        }
+        upgraded.insert(
+            "content".to_owned(),
+            get_content(&media_types, &upgrade_schema(schema), examples),
+        );
+    }
+    Value::Object(upgraded)
+}
+
+fn get_content(
+    media_types: &[String],
+    schema: &Value,
+    examples: Option<&Map<String, Value>>,
+) -> Value {
+    let content = media_types
+        .iter()
+        .map(|media_type| {
+            let mut media = Map::new();
+            media.insert("schema".to_owned(), schema.clone());
+            if let Some(example) = examples.and_then(|it| it.get(media_type)) {
+                media.insert("example".to_owned(), example.clone());
+            }
+            (media_type.clone(), Value::Object(media))
+        })
+        .collect();
+    Value::Object(content)
+}
+
+/// Swagger 2.0 schema is almost OpenAPI 3.0 one, except of file type, x-nullable and discriminator
+fn upgrade_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(swagger_schema) => {
+            let mut upgraded = Map::new();
+            for (key, value) in swagger_schema {
+                let value = match key.as_str() {
+                    "properties" => match value {
+                        Value::Object(properties) => Value::Object(
+                            properties
+                                .iter()
+                                .map(|(name, it)| (name.clone(), upgrade_schema(it)))
+                                .collect(),
+                        ),
+                        _ => value.clone(),
+                    },
+                    "items" | "additionalProperties" | "not" => upgrade_schema(value),
+                    "allOf" | "anyOf" | "oneOf" => match value {
+                        Value::Array(schemas) => {
+                            Value::Array(schemas.iter().map(upgrade_schema).collect())
+                        }
+                        _ => value.clone(),
+                    },
+                    _ => value.clone(),
+                };
+                upgraded.insert(key.clone(), value);
+            }
+
+            if upgraded.get("type").and_then(Value::as_str) == Some("file") {
+                upgraded.insert("type".to_owned(), Value::String("string".to_owned()));
+                upgraded.insert("format".to_owned(), Value::String("binary".to_owned()));
+            }
+            if let Some(nullable) = upgraded.remove("x-nullable") {
+                upgraded.insert("nullable".to_owned(), nullable);
+            }
+            if let Some(Value::String(property_name)) = upgraded.remove("discriminator") {
+                let mut discriminator = Map::new();
+                discriminator.insert("propertyName".to_owned(), Value::String(property_name));
+                upgraded.insert("discriminator".to_owned(), Value::Object(discriminator));
+            }
+            Value::Object(upgraded)
+        }
+        _ => schema.clone(),
+    }
+}
+
+fn upgrade_security_scheme(name: &str, scheme: &Value) -> Value {
+    let mut upgraded = Map::new();
+    copy_keys(scheme, &mut upgraded, &["description"]);
+    copy_extensions(scheme, &mut upgraded);
+
+    match scheme.get("type").and_then(Value::as_str) {
+        Some("basic") => {
+            upgraded.insert("type".to_owned(), Value::String("http".to_owned()));
+            upgraded.insert("scheme".to_owned(), Value::String("basic".to_owned()));
+        }
+        Some("apiKey") => {
+            copy_keys(scheme, &mut upgraded, &["type", "name", "in"]);
+        }
+        Some("oauth2") => {
+            upgraded.insert("type".to_owned(), Value::String("oauth2".to_owned()));
+            let flow_name = match scheme.get("flow").and_then(Value::as_str) {
+                Some("implicit") => "implicit",
+                Some("password") => "password",
+                Some("application") => "clientCredentials",
+                Some("accessCode") => "authorizationCode",
+                flow => {
+                    warn!("Unknown flow [{:?}] of security scheme [{}]", flow, name);
+                    "implicit"
+                }
+            };
+            let mut flow = Map::new();
+            copy_keys(scheme, &mut flow, &["authorizationUrl", "tokenUrl"]);
+            flow.insert(
+                "scopes".to_owned(),
+                scheme
+                    .get("scopes")
+                    .cloned()
+                    .unwrap_or_else(|| Value::Object(Map::new())),
+            );
+            let mut flows = Map::new();
+            flows.insert(flow_name.to_owned(), Value::Object(flow));
+            upgraded.insert("flows".to_owned(), Value::Object(flows));
+        }
+        other => {
+            warn!("Unknown type [{:?}] of security scheme [{}]", other, name);
+            copy_keys(scheme, &mut upgraded, &["type"]);
+        }
+    }
+    Value::Object(upgraded)
+}
+
+/// Local references are moved to places of components in OpenAPI 3.0
+fn rewrite_references(value: &mut Value, swagger: &Map<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get_mut(REFERENCE_KEY) {
+                if let Some(name) = reference.strip_prefix("#/definitions/") {
+                    *reference = format!("#/components/schemas/{}", name);
+                } else if let Some(name) = reference.strip_prefix("#/responses/") {
+                    *reference = format!("#/components/responses/{}", name);
+                } else if let Some(name) = reference.strip_prefix("#/parameters/") {
+                    let kind = match swagger
+                        .get("parameters")
+                        .and_then(|it| it.get(unescape(name)))
+                        .and_then(|it| it.get("in"))
+                        .and_then(Value::as_str)
+                    {
+                        Some("body") => "requestBodies",
+                        _ => "parameters",
+                    };
+                    *reference = format!("#/components/{}/{}", kind, name);
+                }
+            }
+            map.values_mut()
+                .for_each(|it| rewrite_references(it, swagger));
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|it| rewrite_references(it, swagger)),
+        _ => {}
+    }
+}
+
+fn copy_common(from: &Map<String, Value>, to: &mut Map<String, Value>) {
+    for key in COMMON_KEYS {
+        if let Some(value) = from.get(key) {
+            to.insert(key.to_owned(), value.clone());
+        }
+    }
+    for (key, value) in from.iter().filter(|(key, _)| is_extension(key)) {
+        to.insert(key.clone(), value.clone());
+    }
+}
+
+fn copy_keys(from: &Value, to: &mut Map<String, Value>, keys: &[&str]) {
+    for key in keys {
+        if let Some(value) = from.get(key) {
+            to.insert((*key).to_owned(), value.clone());
+        }
+    }
+}
+
+fn copy_extensions(from: &Value, to: &mut Map<String, Value>) {
+    if let Value::Object(from) = from {
+        for (key, value) in from.iter().filter(|(key, _)| is_extension(key)) {
+            to.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+fn is_extension(key: &str) -> bool {
+    key.starts_with("x-")
+}
+
+fn is_array(parameter: &Value) -> bool {
+    parameter.get("type").and_then(Value::as_str) == Some("array")
+}
+
+fn get_name(parameter: &Value) -> &str {
+    parameter
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+fn get_parameter_name(reference: &str) -> &str {
+    reference.rsplit('/').next().unwrap_or(reference)
+}
+
+fn get_array(value: Option<&Value>) -> &[Value] {
+    value
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn get_strings(value: Option<&Value>) -> Vec<String> {
+    get_array(value)
+        .iter()
+        .filter_map(Value::as_str)
+        .map(String::from)
+        .collect()
+}
@@ -0,0 +1,416 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::{
+    services::{
+        locations, references,
+        spec_keys::{
+            self, child_location, ALL_OF_KEY, COMPONENTS_KEY, OPERATION_KEYS, PARAMETERS_KEY,
+            PATHS_KEY, REFERENCE_KEY, SCHEMAS_KEY, WEBHOOKS_KEY,
+        },
+    },
+    structs::common::ParsedSpec,
+    Commands,
+};
+
+/// Schemas with these keys may take required properties from elsewhere
+const OPEN_SCHEMA_KEYS: [&str; 6] = [
+    "allOf",
+    "anyOf",
+    "oneOf",
+    "$ref",
+    "patternProperties",
+    "additionalProperties",
+];
+
+struct Problem {
+    /// Absolute reference to node `document#pointer`
+    location: String,
+    message: String,
+}
+
+struct Validator<'a> {
+    parsed_spec: &'a ParsedSpec,
+    root_document: String,
+    /// External targets of references already checked
+    checked_targets: HashSet<String>,
+    /// Schemas composed by `allOf` of other schemas, their required properties may be declared there
+    composed_schemas: HashSet<String>,
+    /// Required properties absent in properties by location of their schema
+    undeclared_required: Vec<(String, Problem)>,
+    problems: Vec<Problem>,
+}
+
+pub fn validate_command(command: &Commands) -> Result<()> {
+    if let Commands::Validate { spec: spec_path } = command {
+        info!("Validate start for [{:?}]", spec_path);
+
+        let parsed_spec = references::load_spec(spec_path)?;
+        validate_spec(&parsed_spec)?;
+
+        info!("Validate end, no problems found in [{:?}]", spec_path);
+        Ok(())
+    } else {
+        Err(anyhow!("Expected a Validate command"))
+    }
+}
+
+/// All problems of spec and documents referenced from it are reported at once
+pub fn validate_spec(parsed_spec: &ParsedSpec) -> Result<()> {
+    let root_uri = references::absolute_reference("", parsed_spec)?;
+    let (root_document, _) = references::split_reference(&root_uri);
+
+    let mut validator = Validator {
+        parsed_spec,
+        root_document: root_document.to_owned(),
+        checked_targets: HashSet::new(),
+        composed_schemas: HashSet::new(),
+        undeclared_required: Vec::new(),
+        problems: Vec::new(),
+    };
+    validator.check_value(&parsed_spec.spec, &root_uri, false);
+    validator.check_operations(&parsed_spec.spec, &root_uri);
+    validator.add_undeclared_required();
+
+    if validator.problems.is_empty() {
+        return Ok(());
+    }
+    let problems: Vec<String> = validator
+        .problems
+        .iter()
+        .map(|it| validator.describe_problem(it))
+        .collect();
+    Err(anyhow!(
+        "Spec [{:?}] has {} problem(s):\n{}",
+        parsed_spec.path,
+        problems.len(),
+        problems.join("\n")
+    ))
+}
+
+impl Validator<'_> {
+    fn check_value(&mut self, value: &Value, location: &str, is_named_map: bool) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get(REFERENCE_KEY) {
+                    self.check_reference(reference, &child_location(location, REFERENCE_KEY));
+                }
+                if !is_named_map {
+                    self.collect_composed(map, location);
+                    self.check_required(map, location);
+                    self.check_discriminator(map, location);
+                }
+
+                for (key, child) in map {
+                    if spec_keys::is_literal(key, child, is_named_map) {
+                        continue;
+                    }
+                    self.check_value(
+                        child,
+                        &child_location(location, key),
+                        spec_keys::is_named_map(key, is_named_map),
+                    );
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.check_value(item, &child_location(location, &index.to_string()), false);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Target in other document is checked too, it is not a part of walked spec
+    fn check_reference(&mut self, reference: &str, location: &str) {
+        let target_uri = match self.resolve_uri(reference, location) {
+            Ok(target_uri) => target_uri,
+            Err(error) => {
+                self.add_problem(
+                    location,
+                    format!("Invalid $ref [{}]: {:#}", reference, error),
+                );
+                return;
+            }
+        };
+        let target = match references::resolve_value(&target_uri, self.parsed_spec) {
+            Ok(target) => target,
+            Err(error) => {
+                self.add_problem(
+                    location,
+                    format!("Unresolvable $ref [{}]: {:#}", reference, error),
+                );
+                return;
+            }
+        };
+
+        let (document, _) = references::split_reference(&target_uri);
+        if document != self.root_document && self.checked_targets.insert(target_uri.clone()) {
+            self.check_value(&target, &target_uri, false);
+        }
+    }
+
+    fn check_required(&mut self, schema: &Map<String, Value>, location: &str) {
+        let (Some(Value::Array(required)), Some(Value::Object(properties))) =
+            (schema.get("required"), schema.get("properties"))
+        else {
+            return;
+        };
+        if OPEN_SCHEMA_KEYS
+            .iter()
+            .any(|key| schema.get(*key).is_some_and(|it| *it != Value::Bool(false)))
+        {
+            return;
+        }
+
+        for (index, name) in required.iter().enumerate() {
+            if let Some(name) = name.as_str().filter(|it| !properties.contains_key(*it)) {
+                self.undeclared_required.push((
+                    location.to_owned(),
+                    Problem {
+                        location: child_location(
+                            &child_location(location, "required"),
+                            &index.to_string(),
+                        ),
+                        message: format!(
+                            "Required property [{}] is not declared in properties",
+                            name
+                        ),
+                    },
+                ));
+            }
+        }
+    }
+
+    /// Members of `allOf`, inline or referenced, are composed with their siblings and holder
+    fn collect_composed(&mut self, schema: &Map<String, Value>, location: &str) {
+        let Some(Value::Array(members)) = schema.get(ALL_OF_KEY) else {
+            return;
+        };
+        let members_location = child_location(location, ALL_OF_KEY);
+        for (index, member) in members.iter().enumerate() {
+            let member_location = child_location(&members_location, &index.to_string());
+            // Invalid reference of member is reported when the member itself is checked
+            if let Some(reference) = member.get(REFERENCE_KEY).and_then(Value::as_str) {
+                if let Ok(target_uri) = self.resolve_uri(reference, &member_location) {
+                    self.composed_schemas.insert(target_uri);
+                }
+            }
+            self.composed_schemas.insert(member_location);
+        }
+    }
+
+    /// Schema is known to be composed only when the whole spec is walked
+    fn add_undeclared_required(&mut self) {
+        let undeclared_required = std::mem::take(&mut self.undeclared_required);
+        self.problems.extend(
+            undeclared_required
+                .into_iter()
+                .filter(|(schema_location, _)| !self.composed_schemas.contains(schema_location))
+                .map(|(_, problem)| problem),
+        );
+    }
+
+    /// Mapping value is a reference if it looks like one, otherwise a name of schema in components
+    fn check_discriminator(&mut self, schema: &Map<String, Value>, location: &str) {
+        let Some(Value::Object(mapping)) =
+            schema.get("discriminator").and_then(|it| it.get("mapping"))
+        else {
+            return;
+        };
+        let mapping_location =
+            child_location(&child_location(location, "discriminator"), "mapping");
+
+        for (value, target) in mapping {
+            let target_location = child_location(&mapping_location, value);
+            let Some(target) = target.as_str() else {
+                self.add_problem(
+                    &target_location,
+                    format!("Discriminator mapping [{}] is not a string", value),
+                );
+                continue;
+            };
+
+            if spec_keys::is_discriminator_reference(target) {
+                let target_uri = match self.resolve_uri(target, &target_location) {
+                    Ok(target_uri) => target_uri,
+                    Err(error) => {
+                        self.add_problem(
+                            &target_location,
+                            format!(
+                                "Discriminator mapping [{}] targets invalid $ref [{}]: {:#}",
+                                value, target, error
+                            ),
+                        );
+                        continue;
+                    }
+                };
+                if let Err(error) = references::resolve_value(&target_uri, self.parsed_spec) {
+                    self.add_problem(
+                        &target_location,
+                        format!(
+                            "Discriminator mapping [{}] targets unresolvable [{}]: {:#}",
+                            value, target, error
+                        ),
+                    );
+                }
+            } else if !self.is_schema_file(target, &target_location)
+                && self
+                    .parsed_spec
+                    .spec
+                    .get(COMPONENTS_KEY)
+                    .and_then(|it| it.get(SCHEMAS_KEY))
+                    .and_then(|it| it.get(target))
+                    .is_none()
+            {
+                self.add_problem(
+                    &target_location,
+                    format!(
+                        "Discriminator mapping [{}] targets schema [{}] absent in components",
+                        value, target
+                    ),
+                );
+            }
+        }
+    }
+
+    /// OperationIds are unique across paths and webhooks, path template params are declared
+    /// by operation or by its path item
+    fn check_operations(&mut self, spec: &Value, root_uri: &str) {
+        let mut operation_ids: HashMap<String, String> = HashMap::new();
+
+        for key in [PATHS_KEY, WEBHOOKS_KEY] {
+            let Some(Value::Object(items)) = spec.get(key) else {
+                continue;
+            };
+            for (name, item) in items {
+                let item_location = child_location(&child_location(root_uri, key), name);
+                let Some((item, item_location)) = self.resolve_object(item, &item_location) else {
+                    continue;
+                };
+                let item_parameters = self.get_parameters(&item, &item_location);
+
+                for method in OPERATION_KEYS {
+                    let Some(operation) = item.get(method) else {
+                        continue;
+                    };
+                    let operation_location = child_location(&item_location, method);
+
+                    if let Some(operation_id) = operation.get("operationId").and_then(Value::as_str)
+                    {
+                        match operation_ids.get(operation_id) {
+                            Some(first_location) => {
+                                let message = format!(
+                                    "Duplicate operationId [{}], first used at [{}]",
+                                    operation_id,
+                                    locations::describe_node(self.parsed_spec, first_location)
+                                );
+                                self.add_problem(&operation_location, message);
+                            }
+                            None => {
+                                operation_ids
+                                    .insert(operation_id.to_owned(), operation_location.clone());
+                            }
+                        }
+                    }
+
+                    if key == PATHS_KEY {
+                        let mut declared = item_parameters.clone();
+                        declared.extend(self.get_parameters(operation, &operation_location));
+                        for param in get_template_params(name) {
+                            if !declared.contains(&("path".to_owned(), param.to_owned())) {
+                                self.add_problem(
+                                    &operation_location,
+                                    format!(
+                                        "Path param [{}] of [{}] is not declared in parameters",
+                                        param, name
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Location and name of every parameter, unresolvable ones are reported by walk of spec
+    fn get_parameters(&self, holder: &Value, location: &str) -> HashSet<(String, String)> {
+        let Some(Value::Array(parameters)) = holder.get(PARAMETERS_KEY) else {
+            return HashSet::new();
+        };
+        let parameters_location = child_location(location, PARAMETERS_KEY);
+
+        parameters
+            .iter()
+            .enumerate()
+            .filter_map(|(index, it)| {
+                self.resolve_object(
+                    it,
+                    &child_location(&parameters_location, &index.to_string()),
+                )
+            })
+            .filter_map(|(parameter, _)| {
+                Some((
+                    parameter.get("in")?.as_str()?.to_owned(),
+                    parameter.get("name")?.as_str()?.to_owned(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Object itself or target of its reference with location of target
+    fn resolve_object(&self, value: &Value, location: &str) -> Option<(Value, String)> {
+        match value.get(REFERENCE_KEY).and_then(Value::as_str) {
+            Some(reference) => {
+                let target_uri = self.resolve_uri(reference, location).ok()?;
+                let target = references::resolve_value(&target_uri, self.parsed_spec).ok()?;
+                Some((target, target_uri))
+            }
+            None => Some((value.clone(), location.to_owned())),
+        }
+    }
+
+    /// Mapping value with dots only is a file of schema, e.g. `Pet.yaml`, if it resolves
+    fn is_schema_file(&self, target: &str, location: &str) -> bool {
+        target.contains('.')
+            && self
+                .resolve_uri(target, location)
+                .is_ok_and(|it| references::resolve_value(&it, self.parsed_spec).is_ok())
+    }
+
+    /// References are relative to document of node with them
+    fn resolve_uri(&self, reference: &str, location: &str) -> Result<String> {
+        let (document, _) = references::split_reference(location);
+        let base =
+            Url::parse(document).with_context(|| format!("Invalid document URI [{}]", document))?;
+        references::resolve_against(&base, reference)
+    }
+
+    fn add_problem(&mut self, location: &str, message: String) {
+        self.problems.push(Problem {
+            location: location.to_owned(),
+            message,
+        });
+    }
+
+    fn describe_problem(&self, problem: &Problem) -> String {
+        format!(
+            "- [{}] {}",
+            locations::describe_node(self.parsed_spec, &problem.location),
+            problem.message
+        )
+    }
+}
+
+/// Names of `{param}` parts of path template
+fn get_template_params(path: &str) -> Vec<&str> {
+    path.split('{')
+        .skip(1)
+        .filter_map(|it| it.split_once('}').map(|(name, _)| name))
+        .collect()
+}
@@ -1,6 +1,7 @@
 use std::{
-    fs::{self, File},
-    path::Path,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -16,10 +17,17 @@ use openapiv3::{
 };
 
 use crate::{
-    enums::common::Script,
-    holders::context::CACHE,
-    services::{comparators::assert_diff, references},
-    structs::common::{BracketScripts, ParsedSpec},
+    enums::common::{InputKind, MergeConflict, Script, VisitDirective},
+    holders::context::{CLI, EXTENSION_FOR_JSON_SCHEMA_KEYWORDS},
+    services::{
+        comparators::assert_diff,
+        filters, indexing, json_schema, locations, merge, references,
+        spec_keys::{self, PATHS_KEY, WEBHOOKS_KEY},
+        validation,
+    },
+    structs::common::{
+        BracketScripts, JsonSchemaKeywords, ParsedSpec, TraversalStopped, VisitOutput,
+    },
     traits::common::AsSchemaRef,
     Commands,
 };
@@ -28,23 +36,25 @@ use anyhow::{Ok, Result};
 
 use super::{cli, scripts::get_call_id};
 
+const JSON_SCHEMA_EXTENSIONS: [&str; 3] = ["json", "yaml", "yml"];
+
+/// Webhooks of OpenAPI 3.1 spec, parsed apart from OpenAPI 3.0 model
+type Webhooks = IndexMap<String, ReferenceOr<PathItem>>;
+
 pub fn visit_command(command: &Commands) -> Result<()> {
     if let Commands::Translate {
-        spec: spec_path,
+        spec: spec_paths,
         out: out_path,
         clean,
         expected,
+        input_kind,
+        merge_conflict,
         ..
     } = command
     {
-        info!("Command execution start for [{:?}]", spec_path);
+        info!("Command execution start for [{:?}]", spec_paths);
         info!("Output path [{:?}]", out_path);
 
-        CACHE
-            .lock()
-            .expect("Could not lock cache for clean")
-            .clear();
-
         if *clean {
             fs::read_dir(out_path)
                 .with_context(|| format!("Could not found directory for clean [{:?}]", &out_path))?
@@ -55,79 +65,286 @@ pub fn visit_command(command: &Commands) -> Result<()> {
                 .with_context(|| format!("Could not clean old code [{:?}]", &out_path))?;
         }
 
-        let spec_as_json: serde_json::Value = serde_yaml::from_reader(
-            File::open(spec_path)
-                .with_context(|| format!("Could not open spec [{:?}]", &spec_path))?,
-        )
-        .with_context(|| format!("Could not parse spec as yaml [{:?}]", &spec_path))?;
-
-        let openapi: OpenAPI = serde_json::from_value(spec_as_json.clone())
-            .with_context(|| format!("Could not parse spec as OpenAPI v3 [{:?}]", &spec_path))?;
-
-        cli::set_global_lua_parameters(&openapi)?;
-
-        let parsed_spec = ParsedSpec {
-            path: spec_path.to_owned(),
-            spec: Arc::new(spec_as_json),
+        let spec_paths = merge::expand_spec_paths(spec_paths)?;
+        let output = VisitOutput::Code(out_path);
+        let visited = match input_kind {
+            InputKind::Openapi => visit_openapi(&spec_paths, &output, *merge_conflict),
+            InputKind::JsonSchema => visit_json_schemas(&spec_paths, &output),
         };
-
-        Script::VisitSpecStart.call_with_descriptor(
-            spec_path.to_str(),
-            out_path,
-            &(&openapi.openapi, &openapi.extensions),
-        )?;
-
-        visit_spec_info(out_path, &openapi.info)?;
-        visit_servers(out_path, &openapi.servers, &openapi.extensions)?;
-        visit_paths(&parsed_spec, out_path, &openapi.paths)?;
-        visit_security_requirements(out_path, &openapi.security, &openapi.extensions)?;
-        visit_spec_tags(out_path, &openapi.tags, &openapi.extensions)?;
-        visit_external_docs(out_path, &openapi.external_docs)?;
-        visit_spec_components(&parsed_spec, out_path, &openapi.components)?;
-
-        Script::VisitSpecEnd.call_with_descriptor(
-            spec_path.to_str(),
-            out_path,
-            &(&openapi.openapi, &openapi.extensions),
-        )?;
+        // Code written before stop is kept, the rest of spec is intentionally not visited
+        if let Err(error) = visited {
+            if !error.is::<TraversalStopped>() {
+                return Err(error);
+            }
+        }
 
         if let Some(expected_path) = expected {
             assert_diff(out_path, expected_path)?;
         }
-        info!("Command execution end for [{:?}]", spec_path);
+        info!("Command execution end for [{:?}]", spec_paths);
         Ok(())
     } else {
         Err(anyhow!("Expected a Translate command"))
     }
 }
 
+/// Several specs are merged into one and visited once
+fn visit_openapi(
+    spec_paths: &[PathBuf],
+    output: &VisitOutput,
+    merge_conflict: MergeConflict,
+) -> Result<()> {
+    let (parsed_spec, openapi, webhooks) = load_openapi(spec_paths, merge_conflict)?;
+    cli::load_target_scripts()?;
+
+    visit_spec(&parsed_spec, output, &openapi, &webhooks)
+        .map_err(|error| add_location_context(&parsed_spec, error))
+}
+
+/// Spec is merged, validated, filtered and indexed, its index is set to Lua as global before visit
+pub fn load_openapi(
+    spec_paths: &[PathBuf],
+    merge_conflict: MergeConflict,
+) -> Result<(Arc<ParsedSpec>, OpenAPI, Webhooks)> {
+    let mut parsed_spec = merge::load_merged_spec(spec_paths, merge_conflict)?;
+    if CLI.no_validate {
+        info!("Validation of spec is skipped");
+    } else {
+        validation::validate_spec(&parsed_spec)?;
+    }
+    let (openapi, webhooks) = parse_openapi(&parsed_spec)?;
+    let filters = filters::get_filters(&openapi.extensions)?;
+    parsed_spec.selection = filters::select(&parsed_spec, &filters)?;
+
+    let spec_index = indexing::build_index(&parsed_spec)?;
+    let parsed_spec = Arc::new(parsed_spec);
+    cli::set_global_lua_parameters(
+        &openapi.extensions,
+        spec_index,
+        Some(Arc::clone(&parsed_spec)),
+    )?;
+    Ok((parsed_spec, openapi, webhooks))
+}
+
+/// Spec as OpenAPI 3.0 model, OpenAPI 3.1 schemas are normalized and webhooks are parsed apart
+pub fn parse_openapi(parsed_spec: &ParsedSpec) -> Result<(OpenAPI, Webhooks)> {
+    let spec_path = &parsed_spec.path;
+    let mut spec = parsed_spec.spec.as_ref().clone();
+    if json_schema::is_json_schema_dialect(&spec, parsed_spec.input_kind) {
+        json_schema::normalize_document(&mut spec);
+        // OpenAPI 3.1 spec may have only webhooks or components
+        if let Some(spec) = spec.as_object_mut() {
+            spec.entry(PATHS_KEY)
+                .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        }
+    }
+    let webhooks: Webhooks = spec
+        .get(WEBHOOKS_KEY)
+        .map(|it| serde_json::from_value(it.clone()))
+        .transpose()
+        .with_context(|| format!("Could not parse webhooks of spec [{:?}]", &spec_path))?
+        .unwrap_or_default();
+    let openapi: OpenAPI = serde_json::from_value(spec)
+        .with_context(|| format!("Could not parse spec as OpenAPI v3 [{:?}]", &spec_path))?;
+
+    Ok((openapi, webhooks))
+}
+
+/// Bare JSON Schema documents (files or directories of them) are visited as schemas named by file names
+fn visit_json_schemas(spec_paths: &[PathBuf], output: &VisitOutput) -> Result<()> {
+    let schema_paths: Vec<PathBuf> = spec_paths
+        .iter()
+        .map(|it| get_json_schema_paths(it))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+    let spec_path = spec_paths.first().and_then(|it| it.to_str());
+    let mut parsed_schemas = Vec::with_capacity(schema_paths.len());
+    let mut schemas = IndexMap::new();
+    for schema_path in &schema_paths {
+        let mut parsed_spec = references::load_spec(schema_path)?;
+        parsed_spec.input_kind = InputKind::JsonSchema;
+        let mut schema = parsed_spec.spec.as_ref().clone();
+        json_schema::normalize_schema(&mut schema);
+        let schema: Schema = serde_json::from_value(schema)
+            .with_context(|| format!("Could not parse JSON Schema [{:?}]", schema_path))?;
+        let schema_name = get_json_schema_name(schema_path);
+        if schemas.contains_key(&schema_name) {
+            return Err(anyhow!(
+                "Duplicate JSON Schema name [{}] of [{:?}]",
+                schema_name,
+                schema_path
+            ));
+        }
+        schemas.insert(schema_name, ReferenceOr::Item(schema));
+        parsed_schemas.push(Arc::new(parsed_spec));
+    }
+
+    let extensions = IndexMap::new();
+    let spec_index = indexing::build_schemas_index(
+        &schemas
+            .keys()
+            .map(String::as_str)
+            .zip(parsed_schemas.iter().map(Arc::as_ref))
+            .collect::<Vec<_>>(),
+    )?;
+    // Relative references of Lua queries are resolved against the first document
+    cli::set_global_lua_parameters(&extensions, spec_index, parsed_schemas.first().cloned())?;
+    cli::load_target_scripts()?;
+
+    let directive = Script::VisitSpecStart.call_with_directive(
+        spec_path,
+        output,
+        &(None::<&str>, &extensions),
+    )?;
+    if directive != VisitDirective::SkipChildren && !schemas.is_empty() {
+        let directive = Script::VisitSchemasStart.call_with_directive(
+            None,
+            output,
+            &(&schemas, &extensions),
+        )?;
+        if directive != VisitDirective::SkipChildren {
+            for ((schema_name, schema_ref), parsed_spec) in schemas.iter().zip(&parsed_schemas) {
+                visit_schema(parsed_spec, output, Some(schema_name), schema_ref)
+                    .map_err(|error| add_location_context(parsed_spec, error))?;
+            }
+        }
+        Script::VisitSchemasEnd.call_with_descriptor(None, output, &(&schemas, &extensions))?;
+    }
+    Script::VisitSpecEnd.call_with_descriptor(spec_path, output, &(None::<&str>, &extensions))
+}
+
+/// Files of directory with JSON or YAML extension sorted by name, or the file itself
+fn get_json_schema_paths(spec_path: &Path) -> Result<Vec<PathBuf>> {
+    if !spec_path.is_dir() {
+        return Ok(vec![spec_path.to_owned()]);
+    }
+
+    let mut schema_paths: Vec<PathBuf> = fs::read_dir(spec_path)
+        .with_context(|| format!("Could not read JSON Schemas dir [{:?}]", spec_path))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(OsStr::to_str)
+                    .is_some_and(|it| JSON_SCHEMA_EXTENSIONS.contains(&it))
+        })
+        .collect();
+    schema_paths.sort();
+    Ok(schema_paths)
+}
+
+/// File name without extensions, `pet.schema.json` is `pet`
+fn get_json_schema_name(schema_path: &Path) -> String {
+    let file_name = schema_path
+        .file_name()
+        .and_then(OsStr::to_str)
+        .unwrap_or_default();
+    file_name
+        .split_once('.')
+        .map_or(file_name, |it| it.0)
+        .to_owned()
+}
+
+pub fn add_location_context(parsed_spec: &ParsedSpec, error: anyhow::Error) -> anyhow::Error {
+    match locations::describe_visited_location(parsed_spec) {
+        Some(location) => error.context(format!("Failed at [{}]", location)),
+        None => error,
+    }
+}
+
+pub fn visit_spec(
+    parsed_spec: &ParsedSpec,
+    output: &VisitOutput,
+    openapi: &OpenAPI,
+    webhooks: &Webhooks,
+) -> Result<()> {
+    let spec_path = &parsed_spec.path;
+    let directive = Script::VisitSpecStart.call_with_directive(
+        spec_path.to_str(),
+        output,
+        &(&openapi.openapi, &openapi.extensions),
+    )?;
+    if directive != VisitDirective::SkipChildren {
+        visit_spec_info(output, &openapi.info)?;
+        visit_servers(output, &openapi.servers, &openapi.extensions)?;
+        visit_paths(parsed_spec, output, &openapi.paths)?;
+        visit_webhooks(parsed_spec, output, webhooks, &openapi.extensions)?;
+        visit_security_requirements(output, &openapi.security, &openapi.extensions)?;
+        visit_spec_tags(output, &openapi.tags, &openapi.extensions)?;
+        visit_external_docs(output, &openapi.external_docs)?;
+        visit_spec_components(parsed_spec, output, &openapi.components)?;
+    }
+    Script::VisitSpecEnd.call_with_descriptor(
+        spec_path.to_str(),
+        output,
+        &(&openapi.openapi, &openapi.extensions),
+    )
+}
+
+/// Visits child node of current location, location is kept on error to describe where it failed
+fn visit_located<F>(parsed_spec: &ParsedSpec, keys: &[&str], visit: F) -> Result<()>
+where
+    F: FnOnce() -> Result<()>,
+{
+    parsed_spec.enter_child_location(keys);
+    visit()?;
+    parsed_spec.leave_location();
+    Ok(())
+}
+
+/// Visits target of reference at its own location, location is kept on error to describe where it failed
+fn visit_referenced<F>(parsed_spec: &ParsedSpec, reference: &str, visit: F) -> Result<()>
+where
+    F: FnOnce() -> Result<()>,
+{
+    let chain = references::resolve_reference_chain(reference, parsed_spec)?;
+    let target = chain
+        .last()
+        .ok_or_else(|| anyhow!("Reference [{}] is not resolved", reference))?;
+    parsed_spec.enter_location(target.clone());
+    visit()?;
+    parsed_spec.leave_location();
+    Ok(())
+}
+
 pub fn visit_not(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     schema_ref: &ReferenceOr<Schema>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
-    Script::VisitPropertyNotStart.call_with_descriptor(
+    let directive = Script::VisitPropertyNotStart.call_with_directive(
         None,
-        out_path,
+        output,
         &(schema_ref, extensions),
     )?;
-    visit_schema(parsed_spec, out_path, None, schema_ref)?;
-    Script::VisitPropertyNotEnd.call_with_descriptor(None, out_path, &(schema_ref, extensions))
+    if directive != VisitDirective::SkipChildren {
+        visit_schema(parsed_spec, output, None, schema_ref)?;
+    }
+    Script::VisitPropertyNotEnd.call_with_descriptor(None, output, &(schema_ref, extensions))
 }
 
 pub fn visit_schema(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     schema_name: Option<&str>,
     schema_ref: &ReferenceOr<Schema>,
 ) -> Result<()> {
     match schema_ref {
         ReferenceOr::Reference { reference } => {
             let schema = references::resolve_reference::<Schema>(reference, parsed_spec)?;
-            Script::VisitSchemaReferenceStart.call_with_descriptor(
+            if !parsed_spec
+                .enter_reference(&references::absolute_reference(reference, parsed_spec)?)
+            {
+                return visit_schema_cycle(parsed_spec, output, schema_name, reference, &schema);
+            }
+            let directive = Script::VisitSchemaReferenceStart.call_with_directive(
                 get_call_id(schema_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     schema_name,
                     reference,
@@ -136,13 +353,22 @@ pub fn visit_schema(
                         .expect("Unable to get schema from resolved reference")
                         .schema_data
                         .extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
                 ),
             )?;
 
-            visit_schema(parsed_spec, out_path, None, schema)?;
+            let visited = if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_schema(parsed_spec, output, None, &schema)
+                })
+            } else {
+                Ok(())
+            };
+            parsed_spec.leave_reference();
+            visited?;
             Script::VisitSchemaReferenceEnd.call_with_descriptor(
                 get_call_id(schema_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     schema_name,
                     reference,
@@ -159,118 +385,307 @@ pub fn visit_schema(
 
             let schema_data = &schema_item.as_schema().schema_data;
 
-            Script::VisitSchemaStart.call_with_descriptor(
+            let directive = Script::VisitSchemaStart.call_with_directive(
                 schema_name,
-                out_path,
+                output,
                 &(schema_name, &schema_data, &schema_extensions),
             )?;
+            if directive != VisitDirective::SkipChildren {
+                visit_discriminator(output, &schema_data.discriminator)?;
 
-            visit_discriminator(out_path, &schema_data.discriminator)?;
+                visit_external_docs(output, &schema_data.external_docs)?;
 
-            visit_external_docs(out_path, &schema_data.external_docs)?;
+                visit_generic_example(output, &schema_data.example, &schema_data.extensions)?;
 
-            visit_generic_example(out_path, &schema_data.example, &schema_data.extensions)?;
+                visit_schema_default(output, &schema_data.default, &schema_data.extensions)?;
 
-            visit_schema_default(out_path, &schema_data.default, &schema_data.extensions)?;
+                let keywords = get_json_schema_keywords(schema_extensions)?;
+                if let Some(keywords) = keywords.as_ref() {
+                    visit_json_schema_annotations(output, keywords, schema_extensions)?;
+                }
 
-            match &schema_item.as_schema().schema_kind {
-                openapiv3::SchemaKind::Type(type_) => match type_ {
-                    openapiv3::Type::Object(object_descriptor) => {
-                        visit_object(parsed_spec, out_path, object_descriptor, schema_extensions)
-                    }
-                    openapiv3::Type::Array(array_descriptor) => {
-                        visit_array(parsed_spec, out_path, array_descriptor, schema_extensions)
-                    }
-                    // Simple types
-                    openapiv3::Type::String(string_descriptor) => {
-                        visit_string(out_path, string_descriptor, schema_extensions)
-                    }
-                    openapiv3::Type::Number(number_descriptor) => {
-                        visit_number(out_path, number_descriptor, schema_extensions)
-                    }
-                    openapiv3::Type::Integer(integer_descriptor) => {
-                        visit_integer(out_path, integer_descriptor, schema_extensions)
-                    }
-                    openapiv3::Type::Boolean(boolean_descriptor) => {
-                        visit_boolean(out_path, boolean_descriptor, schema_extensions)
-                    }
-                },
-                openapiv3::SchemaKind::OneOf { one_of } => visit_group_of(
-                    parsed_spec,
-                    out_path,
-                    one_of,
-                    &BracketScripts {
-                        start: Script::VisitOneOfStart,
-                        end: Script::VisitOneOfEnd,
+                match &schema_item.as_schema().schema_kind {
+                    openapiv3::SchemaKind::Type(type_) => match type_ {
+                        openapiv3::Type::Object(object_descriptor) => {
+                            visit_object(parsed_spec, output, object_descriptor, schema_extensions)
+                        }
+                        openapiv3::Type::Array(array_descriptor) => {
+                            visit_array(parsed_spec, output, array_descriptor, schema_extensions)
+                        }
+                        // Simple types
+                        openapiv3::Type::String(string_descriptor) => {
+                            visit_string(output, string_descriptor, schema_extensions)
+                        }
+                        openapiv3::Type::Number(number_descriptor) => {
+                            visit_number(output, number_descriptor, schema_extensions)
+                        }
+                        openapiv3::Type::Integer(integer_descriptor) => {
+                            visit_integer(output, integer_descriptor, schema_extensions)
+                        }
+                        openapiv3::Type::Boolean(boolean_descriptor) => {
+                            visit_boolean(output, boolean_descriptor, schema_extensions)
+                        }
                     },
-                    &BracketScripts {
-                        start: Script::VisitOneOfElementStart,
-                        end: Script::VisitOneOfElementEnd,
-                    },
-                    schema_extensions,
-                ),
-                openapiv3::SchemaKind::AllOf { all_of } => visit_group_of(
-                    parsed_spec,
-                    out_path,
-                    all_of,
-                    &BracketScripts {
-                        start: Script::VisitAllOfStart,
-                        end: Script::VisitAllOfEnd,
-                    },
-                    &BracketScripts {
-                        start: Script::VisitAllOfElementStart,
-                        end: Script::VisitAllOfElementEnd,
-                    },
-                    schema_extensions,
-                ),
-                openapiv3::SchemaKind::AnyOf { any_of } => visit_group_of(
-                    parsed_spec,
-                    out_path,
-                    any_of,
-                    &BracketScripts {
-                        start: Script::VisitAnyOfStart,
-                        end: Script::VisitAnyOfEnd,
-                    },
-                    &BracketScripts {
-                        start: Script::VisitAnyOfElementStart,
-                        end: Script::VisitAnyOfElementEnd,
-                    },
-                    schema_extensions,
-                ),
-                openapiv3::SchemaKind::Not { not } => {
-                    let unboxed = not.as_ref();
-                    visit_not(parsed_spec, out_path, unboxed, schema_extensions)
+                    openapiv3::SchemaKind::OneOf { one_of } => visit_group_of(
+                        parsed_spec,
+                        output,
+                        one_of,
+                        &BracketScripts {
+                            start: Script::VisitOneOfStart,
+                            end: Script::VisitOneOfEnd,
+                        },
+                        &BracketScripts {
+                            start: Script::VisitOneOfElementStart,
+                            end: Script::VisitOneOfElementEnd,
+                        },
+                        schema_extensions,
+                    ),
+                    openapiv3::SchemaKind::AllOf { all_of } => visit_group_of(
+                        parsed_spec,
+                        output,
+                        all_of,
+                        &BracketScripts {
+                            start: Script::VisitAllOfStart,
+                            end: Script::VisitAllOfEnd,
+                        },
+                        &BracketScripts {
+                            start: Script::VisitAllOfElementStart,
+                            end: Script::VisitAllOfElementEnd,
+                        },
+                        schema_extensions,
+                    ),
+                    openapiv3::SchemaKind::AnyOf { any_of } => visit_group_of(
+                        parsed_spec,
+                        output,
+                        any_of,
+                        &BracketScripts {
+                            start: Script::VisitAnyOfStart,
+                            end: Script::VisitAnyOfEnd,
+                        },
+                        &BracketScripts {
+                            start: Script::VisitAnyOfElementStart,
+                            end: Script::VisitAnyOfElementEnd,
+                        },
+                        schema_extensions,
+                    ),
+                    openapiv3::SchemaKind::Not { not } => {
+                        let unboxed = not.as_ref();
+                        visit_not(parsed_spec, output, unboxed, schema_extensions)
+                    }
+                    openapiv3::SchemaKind::Any(any_schema) => visit_any_schema(
+                        parsed_spec,
+                        output,
+                        schema_name,
+                        any_schema,
+                        schema_extensions,
+                    ),
+                }?;
+
+                if let Some(keywords) = keywords.as_ref() {
+                    visit_json_schema_applicators(
+                        parsed_spec,
+                        output,
+                        keywords,
+                        schema_extensions,
+                    )?;
                 }
-                openapiv3::SchemaKind::Any(any_schema) => visit_any_schema(
-                    parsed_spec,
-                    out_path,
-                    schema_name,
-                    any_schema,
-                    schema_extensions,
-                ),
-            }?;
-
+            }
             Script::VisitSchemaEnd.call_with_descriptor(
                 schema_name,
-                out_path,
+                output,
                 &(schema_name, schema_data, schema_extensions),
             )
         }
     }
 }
 
+/// Keywords of OpenAPI 3.1 schema, which are kept in extension while spec is normalized
+fn get_json_schema_keywords(
+    extensions: &IndexMap<String, serde_json::Value>,
+) -> Result<Option<JsonSchemaKeywords>> {
+    extensions
+        .get(EXTENSION_FOR_JSON_SCHEMA_KEYWORDS)
+        .map(|it| {
+            serde_json::from_value(it.clone())
+                .with_context(|| format!("Could not parse JSON Schema keywords [{}]", it))
+        })
+        .transpose()
+}
+
+pub fn visit_json_schema_annotations(
+    output: &VisitOutput,
+    keywords: &JsonSchemaKeywords,
+    extensions: &IndexMap<String, serde_json::Value>,
+) -> Result<()> {
+    if let Some(constant) = keywords.constant.as_ref() {
+        Script::VisitConst.call_with_descriptor(None, output, &(constant, extensions))?;
+    }
+
+    if !keywords.examples.is_empty() {
+        Script::VisitSchemaExamples.call_with_descriptor(
+            None,
+            output,
+            &(&keywords.examples, extensions),
+        )?;
+    }
+
+    if keywords.content_media_type.is_some() || keywords.content_encoding.is_some() {
+        Script::VisitContentMediaType.call_with_descriptor(
+            keywords.content_media_type.as_deref(),
+            output,
+            &(
+                &keywords.content_media_type,
+                &keywords.content_encoding,
+                extensions,
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+pub fn visit_json_schema_applicators(
+    parsed_spec: &ParsedSpec,
+    output: &VisitOutput,
+    keywords: &JsonSchemaKeywords,
+    extensions: &IndexMap<String, serde_json::Value>,
+) -> Result<()> {
+    visit_group_of(
+        parsed_spec,
+        output,
+        &keywords.prefix_items,
+        &BracketScripts {
+            start: Script::VisitPrefixItemsStart,
+            end: Script::VisitPrefixItemsEnd,
+        },
+        &BracketScripts {
+            start: Script::VisitPrefixItemStart,
+            end: Script::VisitPrefixItemEnd,
+        },
+        extensions,
+    )?;
+
+    if let Some(it) = keywords.unevaluated_properties.as_ref() {
+        match it {
+            openapiv3::AdditionalProperties::Any(value) => {
+                Script::VisitUnevaluatedPropertiesAny.call_with_descriptor(
+                    None,
+                    output,
+                    &(*value, extensions),
+                )?;
+            }
+            openapiv3::AdditionalProperties::Schema(it) => {
+                let schema_ref = it.as_ref();
+                let directive = Script::VisitUnevaluatedPropertiesStart.call_with_directive(
+                    None,
+                    output,
+                    &(schema_ref, extensions),
+                )?;
+                if directive != VisitDirective::SkipChildren {
+                    visit_schema(parsed_spec, output, None, schema_ref)?;
+                }
+                Script::VisitUnevaluatedPropertiesEnd.call_with_descriptor(
+                    None,
+                    output,
+                    &(schema_ref, extensions),
+                )?;
+            }
+        }
+    }
+
+    if !keywords.dependent_schemas.is_empty() {
+        let directive = Script::VisitDependentSchemasStart.call_with_directive(
+            None,
+            output,
+            &(&keywords.dependent_schemas, extensions),
+        )?;
+        if directive != VisitDirective::SkipChildren {
+            for (property_name, schema_ref) in &keywords.dependent_schemas {
+                let directive = Script::VisitDependentSchemaStart.call_with_directive(
+                    Some(property_name),
+                    output,
+                    &(property_name, schema_ref, extensions),
+                )?;
+                if directive != VisitDirective::SkipChildren {
+                    visit_schema(parsed_spec, output, None, schema_ref)?;
+                }
+                Script::VisitDependentSchemaEnd.call_with_descriptor(
+                    Some(property_name),
+                    output,
+                    &(property_name, schema_ref, extensions),
+                )?;
+            }
+        }
+        Script::VisitDependentSchemasEnd.call_with_descriptor(
+            None,
+            output,
+            &(&keywords.dependent_schemas, extensions),
+        )?;
+    }
+
+    let conditions = [
+        (
+            &keywords.if_schema,
+            Script::VisitIfStart,
+            Script::VisitIfEnd,
+        ),
+        (
+            &keywords.then_schema,
+            Script::VisitThenStart,
+            Script::VisitThenEnd,
+        ),
+        (
+            &keywords.else_schema,
+            Script::VisitElseStart,
+            Script::VisitElseEnd,
+        ),
+    ];
+    for (schema_ref, start, end) in conditions {
+        if let Some(schema_ref) = schema_ref {
+            let directive = start.call_with_directive(None, output, &(schema_ref, extensions))?;
+            if directive != VisitDirective::SkipChildren {
+                visit_schema(parsed_spec, output, None, schema_ref)?;
+            }
+            end.call_with_descriptor(None, output, &(schema_ref, extensions))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn visit_schema_cycle(
+    parsed_spec: &ParsedSpec,
+    output: &VisitOutput,
+    schema_name: Option<&str>,
+    reference: &str,
+    schema: &ReferenceOr<Schema>,
+) -> Result<()> {
+    Script::VisitSchemaCycle.call_with_descriptor(
+        get_call_id(schema_name, reference).as_deref(),
+        output,
+        &(
+            schema_name,
+            reference,
+            parsed_spec.get_references_chain(),
+            &schema
+                .as_item()
+                .expect("Unable to get schema from resolved reference")
+                .schema_data
+                .extensions,
+        ),
+    )
+}
+
 pub fn visit_response(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     response_name: Option<&str>,
     response_ref: &ReferenceOr<Response>,
 ) -> Result<()> {
     match response_ref {
         ReferenceOr::Reference { reference } => {
             let response = references::resolve_reference::<Response>(reference, parsed_spec)?;
-            Script::VisitResponseReferenceStart.call_with_descriptor(
+            let directive = Script::VisitResponseReferenceStart.call_with_directive(
                 get_call_id(response_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     response_name,
                     reference,
@@ -278,13 +693,17 @@ pub fn visit_response(
                         .as_item()
                         .expect("Unable to get extensions from resolved response")
                         .extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
                 ),
             )?;
-
-            visit_response(parsed_spec, out_path, None, response)?;
+            if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_response(parsed_spec, output, None, &response)
+                })?;
+            }
             Script::VisitResponseReferenceEnd.call_with_descriptor(
                 get_call_id(response_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     response_name,
                     reference,
@@ -298,31 +717,21 @@ pub fn visit_response(
         ReferenceOr::Item(response) => {
             let response_extensions = &response.extensions;
 
-            Script::VisitResponseStart.call_with_descriptor(
+            let directive = Script::VisitResponseStart.call_with_directive(
                 response_name,
-                out_path,
+                output,
                 &(&response_name, response, &response_extensions),
             )?;
+            if directive != VisitDirective::SkipChildren {
+                visit_headers(parsed_spec, output, &response.headers, response_extensions)?;
 
-            visit_headers(
-                parsed_spec,
-                out_path,
-                &response.headers,
-                response_extensions,
-            )?;
-
-            visit_media_types(
-                parsed_spec,
-                out_path,
-                &response.content,
-                response_extensions,
-            )?;
-
-            visit_links(parsed_spec, out_path, &response.links, response_extensions)?;
+                visit_media_types(parsed_spec, output, &response.content, response_extensions)?;
 
+                visit_links(parsed_spec, output, &response.links, response_extensions)?;
+            }
             Script::VisitResponseEnd.call_with_descriptor(
                 response_name,
-                out_path,
+                output,
                 &(response_name, response, response_extensions),
             )
         }
@@ -330,227 +739,230 @@ pub fn visit_response(
 }
 
 pub fn visit_string(
-    out_path: &Path,
+    output: &VisitOutput,
     string_descriptor: &StringType,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
-    Script::VisitStringProperty.call_with_descriptor(
-        None,
-        out_path,
-        &(string_descriptor, extensions),
-    )
+    Script::VisitStringProperty.call_with_descriptor(None, output, &(string_descriptor, extensions))
 }
 
 pub fn visit_any_schema(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     schema_name: Option<&str>,
     any_schema_descriptor: &AnySchema,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
-    Script::VisitAnySchemaStart.call_with_descriptor(
+    let directive = Script::VisitAnySchemaStart.call_with_directive(
         schema_name,
-        out_path,
+        output,
         &(any_schema_descriptor, extensions),
     )?;
-    if !any_schema_descriptor.all_of.is_empty() {
-        visit_group_of(
-            parsed_spec,
-            out_path,
-            &any_schema_descriptor.all_of,
-            &BracketScripts {
-                start: Script::VisitAllOfStart,
-                end: Script::VisitAllOfEnd,
-            },
-            &BracketScripts {
-                start: Script::VisitAllOfElementStart,
-                end: Script::VisitAllOfElementEnd,
-            },
-            extensions,
-        )?;
-    }
-
-    if !any_schema_descriptor.any_of.is_empty() {
-        visit_group_of(
-            parsed_spec,
-            out_path,
-            &any_schema_descriptor.any_of,
-            &BracketScripts {
-                start: Script::VisitAnyOfStart,
-                end: Script::VisitAnyOfEnd,
-            },
-            &BracketScripts {
-                start: Script::VisitAnyOfElementStart,
-                end: Script::VisitAnyOfElementEnd,
-            },
-            extensions,
-        )?;
-    }
-    if !any_schema_descriptor.one_of.is_empty() {
-        visit_group_of(
-            parsed_spec,
-            out_path,
-            &any_schema_descriptor.one_of,
-            &BracketScripts {
-                start: Script::VisitOneOfStart,
-                end: Script::VisitOneOfEnd,
-            },
-            &BracketScripts {
-                start: Script::VisitOneOfElementStart,
-                end: Script::VisitOneOfElementEnd,
-            },
-            extensions,
-        )?;
-    }
-
-    if let Some(schema) = any_schema_descriptor.not.as_ref() {
-        visit_not(parsed_spec, out_path, schema, extensions)?;
-    }
-
-    // visit_object(parsed_spec, out_path, object_description, extensions, )?;
-    if let Some(schema) = any_schema_descriptor.typ.as_ref() {
-        match schema.as_str() {
-            "string" => visit_string(
-                out_path,
-                &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
-                extensions,
-            ),
-            "number" => visit_number(
-                out_path,
-                &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
-                extensions,
-            ),
-            "integer" => visit_integer(
-                out_path,
-                &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
-                extensions,
-            ),
-            "boolean" => visit_boolean(
-                out_path,
-                &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
+    if directive != VisitDirective::SkipChildren {
+        if !any_schema_descriptor.all_of.is_empty() {
+            visit_group_of(
+                parsed_spec,
+                output,
+                &any_schema_descriptor.all_of,
+                &BracketScripts {
+                    start: Script::VisitAllOfStart,
+                    end: Script::VisitAllOfEnd,
+                },
+                &BracketScripts {
+                    start: Script::VisitAllOfElementStart,
+                    end: Script::VisitAllOfElementEnd,
+                },
                 extensions,
-            ),
-            "array" => visit_array(
+            )?;
+        }
+
+        if !any_schema_descriptor.any_of.is_empty() {
+            visit_group_of(
                 parsed_spec,
-                out_path,
-                &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
+                output,
+                &any_schema_descriptor.any_of,
+                &BracketScripts {
+                    start: Script::VisitAnyOfStart,
+                    end: Script::VisitAnyOfEnd,
+                },
+                &BracketScripts {
+                    start: Script::VisitAnyOfElementStart,
+                    end: Script::VisitAnyOfElementEnd,
+                },
                 extensions,
-            ),
-            "object" => visit_object(
+            )?;
+        }
+        if !any_schema_descriptor.one_of.is_empty() {
+            visit_group_of(
                 parsed_spec,
-                out_path,
-                &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
+                output,
+                &any_schema_descriptor.one_of,
+                &BracketScripts {
+                    start: Script::VisitOneOfStart,
+                    end: Script::VisitOneOfEnd,
+                },
+                &BracketScripts {
+                    start: Script::VisitOneOfElementStart,
+                    end: Script::VisitOneOfElementEnd,
+                },
                 extensions,
-            ),
-            _ => Ok(()),
-        }?;
-    }
+            )?;
+        }
+
+        if let Some(schema) = any_schema_descriptor.not.as_ref() {
+            visit_not(parsed_spec, output, schema, extensions)?;
+        }
 
+        // visit_object(parsed_spec, output, object_description, extensions, )?;
+        if let Some(schema) = any_schema_descriptor.typ.as_ref() {
+            match schema.as_str() {
+                "string" => visit_string(
+                    output,
+                    &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
+                    extensions,
+                ),
+                "number" => visit_number(
+                    output,
+                    &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
+                    extensions,
+                ),
+                "integer" => visit_integer(
+                    output,
+                    &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
+                    extensions,
+                ),
+                "boolean" => visit_boolean(
+                    output,
+                    &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
+                    extensions,
+                ),
+                "array" => visit_array(
+                    parsed_spec,
+                    output,
+                    &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
+                    extensions,
+                ),
+                "object" => visit_object(
+                    parsed_spec,
+                    output,
+                    &serde_json::from_value(serde_json::to_value(any_schema_descriptor)?)?,
+                    extensions,
+                ),
+                _ => Ok(()),
+            }?;
+        }
+    }
     Script::VisitAnySchemaEnd.call_with_descriptor(
         schema_name,
-        out_path,
+        output,
         &(any_schema_descriptor, extensions),
     )
 }
 
 pub fn visit_number(
-    out_path: &Path,
+    output: &VisitOutput,
     number_descriptor: &NumberType,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
-    Script::VisitNumberProperty.call_with_descriptor(
-        None,
-        out_path,
-        &(number_descriptor, extensions),
-    )
+    Script::VisitNumberProperty.call_with_descriptor(None, output, &(number_descriptor, extensions))
 }
 
 pub fn visit_integer(
-    out_path: &Path,
+    output: &VisitOutput,
     integer_descriptor: &IntegerType,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     Script::VisitIntegerProperty.call_with_descriptor(
         None,
-        out_path,
+        output,
         &(integer_descriptor, extensions),
     )
 }
 
 pub fn visit_array(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     array_descriptor: &ArrayType,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
-    Script::VisitArrayPropertyStart.call_with_descriptor(
+    let directive = Script::VisitArrayPropertyStart.call_with_directive(
         None,
-        out_path,
+        output,
         &(array_descriptor, extensions),
     )?;
-    if let Some(it) = &array_descriptor.items {
-        let unboxed = it.clone().unbox();
-        visit_schema(parsed_spec, out_path, None, &unboxed)?;
+    if directive != VisitDirective::SkipChildren {
+        if let Some(it) = &array_descriptor.items {
+            let unboxed = it.clone().unbox();
+            visit_located(parsed_spec, &["items"], || {
+                visit_schema(parsed_spec, output, None, &unboxed)
+            })?;
+        }
     }
-
     Script::VisitArrayPropertyEnd.call_with_descriptor(
         None,
-        out_path,
+        output,
         &(array_descriptor, extensions),
     )
 }
 
 pub fn visit_boolean(
-    out_path: &Path,
+    output: &VisitOutput,
     boolean_descriptor: &BooleanType,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     Script::VisitBooleanProperty.call_with_descriptor(
         None,
-        out_path,
+        output,
         &(boolean_descriptor, extensions),
     )
 }
 
 pub fn visit_group_of(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     schemas: &[ReferenceOr<Schema>],
     braced_scripts: &BracketScripts,
     element_scripts: &BracketScripts,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !schemas.is_empty() {
-        braced_scripts
-            .start
-            .call_with_descriptor(None, out_path, &(schemas, &extensions))?;
-        schemas.iter().enumerate().try_for_each(|(index, schema)| {
-            element_scripts.start.call_with_descriptor(
-                Some(&index.to_string()),
-                out_path,
-                &(schema, &extensions),
-            )?;
-            visit_schema(parsed_spec, out_path, None, schema)?;
-            element_scripts.end.call_with_descriptor(
-                Some(&index.to_string()),
-                out_path,
-                &(schema, &extensions),
-            )
-        })?;
-
+        let directive =
+            braced_scripts
+                .start
+                .call_with_directive(None, output, &(schemas, &extensions))?;
+        if directive != VisitDirective::SkipChildren {
+            schemas.iter().enumerate().try_for_each(|(index, schema)| {
+                let directive = element_scripts.start.call_with_directive(
+                    Some(&index.to_string()),
+                    output,
+                    &(schema, &extensions),
+                )?;
+                if directive != VisitDirective::SkipChildren {
+                    visit_schema(parsed_spec, output, None, schema)?;
+                }
+                element_scripts.end.call_with_descriptor(
+                    Some(&index.to_string()),
+                    output,
+                    &(schema, &extensions),
+                )
+            })?;
+        }
         braced_scripts
             .end
-            .call_with_descriptor(None, out_path, &(schemas, extensions))
+            .call_with_descriptor(None, output, &(schemas, extensions))
     } else {
         Ok(())
     }
 }
 
-pub fn visit_discriminator(out_path: &Path, dicriminator: &Option<Discriminator>) -> Result<()> {
+pub fn visit_discriminator(
+    output: &VisitOutput,
+    dicriminator: &Option<Discriminator>,
+) -> Result<()> {
     if let Some(discriminator) = dicriminator.as_ref() {
         Script::VisitDiscriminator.call_with_descriptor(
             Some(&discriminator.property_name),
-            out_path,
+            output,
             &(discriminator, &discriminator.extensions),
         )
     } else {
@@ -559,39 +971,39 @@ pub fn visit_discriminator(out_path: &Path, dicriminator: &Option<Discriminator>
 }
 
 pub fn visit_generic_example(
-    out_path: &Path,
+    output: &VisitOutput,
     example: &Option<serde_json::Value>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if let Some(example) = example {
-        Script::VisitGenericExample.call_with_descriptor(None, out_path, &(&example, extensions))
+        Script::VisitGenericExample.call_with_descriptor(None, output, &(&example, extensions))
     } else {
         Ok(())
     }
 }
 
 pub fn visit_generic_parameter(
-    out_path: &Path,
+    output: &VisitOutput,
     parameter_name: &str,
     parameter: &serde_json::Value,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     Script::VisitGenericParameter.call_with_descriptor(
         Some(parameter_name),
-        out_path,
+        output,
         &(parameter_name, parameter, extensions),
     )
 }
 
 pub fn visit_generic_request_body(
-    out_path: &Path,
+    output: &VisitOutput,
     body: &Option<serde_json::Value>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if let Some(body_json) = body {
         Script::VisitGenericRequestBody.call_with_descriptor(
             None,
-            out_path,
+            output,
             &(&body_json, extensions),
         )
     } else {
@@ -601,22 +1013,22 @@ pub fn visit_generic_request_body(
 
 pub fn visit_media_type_encodings(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     encodings: &IndexMap<String, Encoding>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !encodings.is_empty() {
-        Script::VisitEncodingsStart.call_with_descriptor(
+        let directive = Script::VisitEncodingsStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(&encodings, extensions),
         )?;
-
-        for encoding in encodings {
-            visit_media_type_encoding(parsed_spec, out_path, encoding.0, encoding.1)?;
+        if directive != VisitDirective::SkipChildren {
+            for encoding in encodings {
+                visit_media_type_encoding(parsed_spec, output, encoding.0, encoding.1)?;
+            }
         }
-
-        Script::VisitEncodingsEnd.call_with_descriptor(None, out_path, &(&encodings, extensions))
+        Script::VisitEncodingsEnd.call_with_descriptor(None, output, &(&encodings, extensions))
     } else {
         Ok(())
     }
@@ -624,41 +1036,37 @@ pub fn visit_media_type_encodings(
 
 pub fn visit_media_type_encoding(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     encoding_name: &str,
     encoding: &Encoding,
 ) -> Result<()> {
-    Script::VisitEncodingStart.call_with_descriptor(
+    let directive = Script::VisitEncodingStart.call_with_directive(
         Some(encoding_name),
-        out_path,
+        output,
         &(&encoding_name, &encoding, &encoding.extensions),
     )?;
-    visit_headers(
-        parsed_spec,
-        out_path,
-        &encoding.headers,
-        &encoding.extensions,
-    )?;
-
+    if directive != VisitDirective::SkipChildren {
+        visit_headers(parsed_spec, output, &encoding.headers, &encoding.extensions)?;
+    }
     Script::VisitEncodingEnd.call_with_descriptor(
         Some(encoding_name),
-        out_path,
+        output,
         &(&encoding_name, &encoding, &encoding.extensions),
     )
 }
 
 pub fn visit_example(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     example_name: Option<&str>,
     example_ref: &ReferenceOr<Example>,
 ) -> Result<()> {
     match example_ref {
         ReferenceOr::Reference { reference } => {
             let example = references::resolve_reference::<Example>(reference, parsed_spec)?;
-            Script::VisitExampleReferenceStart.call_with_descriptor(
+            let directive = Script::VisitExampleReferenceStart.call_with_directive(
                 get_call_id(example_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     example_name,
                     reference,
@@ -666,13 +1074,17 @@ pub fn visit_example(
                         .as_item()
                         .expect("Unable to get example from resolved reference")
                         .extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
                 ),
             )?;
-
-            visit_example(parsed_spec, out_path, None, example)?;
+            if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_example(parsed_spec, output, None, &example)
+                })?;
+            }
             Script::VisitExampleReferenceEnd.call_with_descriptor(
                 get_call_id(example_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     example_name,
                     reference,
@@ -684,17 +1096,17 @@ pub fn visit_example(
             )
         }
         ReferenceOr::Item(example) => {
-            Script::VisitExampleStart.call_with_descriptor(
+            let directive = Script::VisitExampleStart.call_with_directive(
                 example_name,
-                out_path,
+                output,
                 &(example_name, &example, &example.extensions),
             )?;
-
-            visit_generic_example(out_path, &example.value, &example.extensions)?;
-
+            if directive != VisitDirective::SkipChildren {
+                visit_generic_example(output, &example.value, &example.extensions)?;
+            }
             Script::VisitExampleEnd.call_with_descriptor(
                 example_name,
-                out_path,
+                output,
                 &(example_name, &example, &example.extensions),
             )
         }
@@ -703,7 +1115,7 @@ pub fn visit_example(
 
 pub fn visit_request_body(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     request_body_name: Option<&str>,
     request_body_ref: &ReferenceOr<RequestBody>,
 ) -> Result<()> {
@@ -711,9 +1123,9 @@ pub fn visit_request_body(
         ReferenceOr::Reference { reference } => {
             let request_body =
                 references::resolve_reference::<RequestBody>(reference, parsed_spec)?;
-            Script::VisitRequestBodyReferenceStart.call_with_descriptor(
+            let directive = Script::VisitRequestBodyReferenceStart.call_with_directive(
                 get_call_id(request_body_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     &request_body_name,
                     &reference,
@@ -721,13 +1133,17 @@ pub fn visit_request_body(
                         .as_item()
                         .expect("Unable to get extensions from resolved request body")
                         .extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
                 ),
             )?;
-
-            visit_request_body(parsed_spec, out_path, None, request_body)?;
+            if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_request_body(parsed_spec, output, None, &request_body)
+                })?;
+            }
             Script::VisitRequestBodyReferenceEnd.call_with_descriptor(
                 get_call_id(request_body_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     &request_body_name,
                     &reference,
@@ -739,22 +1155,22 @@ pub fn visit_request_body(
             )
         }
         ReferenceOr::Item(request_body) => {
-            Script::VisitRequestBodyStart.call_with_descriptor(
+            let directive = Script::VisitRequestBodyStart.call_with_directive(
                 request_body_name,
-                out_path,
+                output,
                 &(&request_body_name, &request_body, &request_body.extensions),
             )?;
-
-            visit_media_types(
-                parsed_spec,
-                out_path,
-                &request_body.content,
-                &request_body.extensions,
-            )?;
-
+            if directive != VisitDirective::SkipChildren {
+                visit_media_types(
+                    parsed_spec,
+                    output,
+                    &request_body.content,
+                    &request_body.extensions,
+                )?;
+            }
             Script::VisitRequestBodyEnd.call_with_descriptor(
                 request_body_name,
-                out_path,
+                output,
                 &(&request_body_name, &request_body, &request_body.extensions),
             )
         }
@@ -763,50 +1179,53 @@ pub fn visit_request_body(
 
 pub fn visit_parameter_schema_or_content(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     parameter_name: Option<&str>,
     parameter_schema_or_content: &ParameterSchemaOrContent,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
-    Script::VisitParameterSchemaOrContentStart.call_with_descriptor(
+    let directive = Script::VisitParameterSchemaOrContentStart.call_with_directive(
         parameter_name,
-        out_path,
+        output,
         &(&parameter_name, &parameter_schema_or_content, extensions),
     )?;
-    match parameter_schema_or_content {
-        ParameterSchemaOrContent::Schema(schema_ref) => {
-            visit_schema(parsed_spec, out_path, None, schema_ref)?;
-        }
-        ParameterSchemaOrContent::Content(media_types) => {
-            visit_media_types(parsed_spec, out_path, media_types, extensions)?;
+    if directive != VisitDirective::SkipChildren {
+        match parameter_schema_or_content {
+            ParameterSchemaOrContent::Schema(schema_ref) => {
+                visit_schema(parsed_spec, output, None, schema_ref)?;
+            }
+            ParameterSchemaOrContent::Content(media_types) => {
+                visit_media_types(parsed_spec, output, media_types, extensions)?;
+            }
         }
     }
-
     Script::VisitParameterSchemaOrContentEnd.call_with_descriptor(
         parameter_name,
-        out_path,
+        output,
         &(&parameter_name, &parameter_schema_or_content, extensions),
     )
 }
 
 pub fn visit_media_types(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     media_types: &IndexMap<String, MediaType>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !media_types.is_empty() {
-        Script::VisitMediaTypesStart.call_with_descriptor(
+        let directive = Script::VisitMediaTypesStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(media_types, extensions),
         )?;
-
-        for media_type in media_types {
-            visit_media_type(parsed_spec, out_path, media_type.0, media_type.1)?;
+        if directive != VisitDirective::SkipChildren {
+            for media_type in media_types {
+                visit_located(parsed_spec, &["content", media_type.0], || {
+                    visit_media_type(parsed_spec, output, media_type.0, media_type.1)
+                })?;
+            }
         }
-
-        Script::VisitMediaTypesEnd.call_with_descriptor(None, out_path, &(media_types, extensions))
+        Script::VisitMediaTypesEnd.call_with_descriptor(None, output, &(media_types, extensions))
     } else {
         Ok(())
     }
@@ -814,30 +1233,32 @@ pub fn visit_media_types(
 
 pub fn visit_callbacks(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     operation_callbacks: &IndexMap<String, ReferenceOr<Callback>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !operation_callbacks.is_empty() {
-        Script::VisitAsyncCallbacksStart.call_with_descriptor(
+        let directive = Script::VisitAsyncCallbacksStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(operation_callbacks, &extensions),
         )?;
-
-        for callbacks in operation_callbacks {
-            visit_callback(
-                parsed_spec,
-                out_path,
-                Some(callbacks.0),
-                callbacks.1,
-                extensions,
-            )?;
+        if directive != VisitDirective::SkipChildren {
+            for callbacks in operation_callbacks {
+                visit_located(parsed_spec, &["callbacks", callbacks.0], || {
+                    visit_callback(
+                        parsed_spec,
+                        output,
+                        Some(callbacks.0),
+                        callbacks.1,
+                        extensions,
+                    )
+                })?;
+            }
         }
-
         Script::VisitAsyncCallbacksEnd.call_with_descriptor(
             None,
-            out_path,
+            output,
             &(operation_callbacks, &extensions),
         )
     } else {
@@ -847,18 +1268,21 @@ pub fn visit_callbacks(
 
 pub fn visit_links(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     links: &IndexMap<String, ReferenceOr<Link>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !links.is_empty() {
-        Script::VisitLinksStart.call_with_descriptor(None, out_path, &(links, &extensions))?;
-
-        for link in links {
-            visit_link(parsed_spec, out_path, Some(link.0), link.1)?;
+        let directive =
+            Script::VisitLinksStart.call_with_directive(None, output, &(links, &extensions))?;
+        if directive != VisitDirective::SkipChildren {
+            for link in links {
+                visit_located(parsed_spec, &["links", link.0], || {
+                    visit_link(parsed_spec, output, Some(link.0), link.1)
+                })?;
+            }
         }
-
-        Script::VisitLinksEnd.call_with_descriptor(None, out_path, &(links, &extensions))
+        Script::VisitLinksEnd.call_with_descriptor(None, output, &(links, &extensions))
     } else {
         Ok(())
     }
@@ -866,16 +1290,16 @@ pub fn visit_links(
 
 pub fn visit_link(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     link_name: Option<&str>,
     link_ref: &ReferenceOr<Link>,
 ) -> Result<()> {
     match link_ref {
         ReferenceOr::Reference { reference } => {
             let link = references::resolve_reference::<Link>(reference, parsed_spec)?;
-            Script::VisitLinkReferenceStart.call_with_descriptor(
+            let directive = Script::VisitLinkReferenceStart.call_with_directive(
                 get_call_id(link_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     link_name,
                     reference,
@@ -883,12 +1307,17 @@ pub fn visit_link(
                         .as_item()
                         .expect("Unable to get extensions from resolved link reference")
                         .extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
                 ),
             )?;
-            visit_link(parsed_spec, out_path, None, link)?;
+            if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_link(parsed_spec, output, None, &link)
+                })?;
+            }
             Script::VisitLinkReferenceEnd.call_with_descriptor(
                 get_call_id(link_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     link_name,
                     reference,
@@ -900,23 +1329,23 @@ pub fn visit_link(
             )
         }
         ReferenceOr::Item(link) => {
-            Script::VisitLinkStart.call_with_descriptor(
+            let directive = Script::VisitLinkStart.call_with_directive(
                 link_name,
-                out_path,
+                output,
                 &(link_name, link, &link.extensions),
             )?;
+            if directive != VisitDirective::SkipChildren {
+                visit_generic_request_body(output, &link.request_body, &link.extensions)?;
 
-            visit_generic_request_body(out_path, &link.request_body, &link.extensions)?;
-
-            visit_generic_parameters(out_path, &link.parameters, &link.extensions)?;
+                visit_generic_parameters(output, &link.parameters, &link.extensions)?;
 
-            if let Some(server) = &link.server {
-                visit_server(out_path, server)?;
+                if let Some(server) = &link.server {
+                    visit_server(output, server)?;
+                }
             }
-
             Script::VisitLinkEnd.call_with_descriptor(
                 link_name,
-                out_path,
+                output,
                 &(link_name, link, &link.extensions),
             )
         }
@@ -925,7 +1354,7 @@ pub fn visit_link(
 
 pub fn visit_callback(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     callbacks_name: Option<&str>,
     callbacks: &ReferenceOr<Callback>,
     extensions: &IndexMap<String, serde_json::Value>,
@@ -933,33 +1362,43 @@ pub fn visit_callback(
     match callbacks {
         ReferenceOr::Reference { reference } => {
             let callback = references::resolve_reference::<Callback>(reference, parsed_spec)?;
-            Script::VisitAsyncCallbackReferenceStart.call_with_descriptor(
+            let directive = Script::VisitAsyncCallbackReferenceStart.call_with_directive(
                 get_call_id(callbacks_name, reference).as_deref(),
-                out_path,
-                &(callbacks_name, reference, &extensions),
+                output,
+                &(
+                    callbacks_name,
+                    reference,
+                    &extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
+                ),
             )?;
-
-            visit_callback(parsed_spec, out_path, None, callback, extensions)?;
+            if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_callback(parsed_spec, output, None, &callback, extensions)
+                })?;
+            }
             Script::VisitAsyncCallbackReferenceEnd.call_with_descriptor(
                 get_call_id(callbacks_name, reference).as_deref(),
-                out_path,
+                output,
                 &(callbacks_name, reference, &extensions),
             )
         }
         ReferenceOr::Item(callback) => {
-            Script::VisitAsyncCallbackStart.call_with_descriptor(
+            let directive = Script::VisitAsyncCallbackStart.call_with_directive(
                 callbacks_name,
-                out_path,
+                output,
                 &(callbacks_name, callback, &extensions),
             )?;
-
-            for it in callback {
-                visit_path_item(parsed_spec, out_path, Some(it.0), it.1)?;
+            if directive != VisitDirective::SkipChildren {
+                for it in callback {
+                    visit_located(parsed_spec, &[it.0], || {
+                        visit_path_item(parsed_spec, output, Some(it.0), it.1)
+                    })?;
+                }
             }
-
             Script::VisitAsyncCallbackEnd.call_with_descriptor(
                 callbacks_name,
-                out_path,
+                output,
                 &(callbacks_name, callback, &extensions),
             )
         }
@@ -968,60 +1407,63 @@ pub fn visit_callback(
 
 pub fn visit_media_type(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     media_type_name: &str,
     media_type: &MediaType,
 ) -> Result<()> {
-    Script::VisitMediaTypeStart.call_with_descriptor(
+    let directive = Script::VisitMediaTypeStart.call_with_directive(
         Some(media_type_name),
-        out_path,
+        output,
         &(&media_type_name, media_type, &media_type.extensions),
     )?;
-    if let Some(schema_ref) = &media_type.schema {
-        visit_schema(parsed_spec, out_path, None, schema_ref)?;
-    }
-
-    visit_generic_example(out_path, &media_type.example, &media_type.extensions)?;
+    if directive != VisitDirective::SkipChildren {
+        if let Some(schema_ref) = &media_type.schema {
+            visit_schema(parsed_spec, output, None, schema_ref)?;
+        }
 
-    visit_examples(
-        parsed_spec,
-        out_path,
-        &media_type.examples,
-        &media_type.extensions,
-    )?;
+        visit_generic_example(output, &media_type.example, &media_type.extensions)?;
 
-    visit_media_type_encodings(
-        parsed_spec,
-        out_path,
-        &media_type.encoding,
-        &media_type.extensions,
-    )?;
+        visit_examples(
+            parsed_spec,
+            output,
+            &media_type.examples,
+            &media_type.extensions,
+        )?;
 
+        visit_media_type_encodings(
+            parsed_spec,
+            output,
+            &media_type.encoding,
+            &media_type.extensions,
+        )?;
+    }
     Script::VisitMediaTypeEnd.call_with_descriptor(
         Some(media_type_name),
-        out_path,
+        output,
         &(&media_type_name, media_type, &media_type.extensions),
     )
 }
 
 pub fn visit_examples(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     examples: &IndexMap<String, ReferenceOr<Example>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !examples.is_empty() {
-        Script::VisitExamplesStart.call_with_descriptor(
+        let directive = Script::VisitExamplesStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(&examples, extensions),
         )?;
-
-        for it in examples {
-            visit_example(parsed_spec, out_path, Some(it.0), it.1)?;
+        if directive != VisitDirective::SkipChildren {
+            for it in examples {
+                visit_located(parsed_spec, &["examples", it.0], || {
+                    visit_example(parsed_spec, output, Some(it.0), it.1)
+                })?;
+            }
         }
-
-        Script::VisitExamplesEnd.call_with_descriptor(None, out_path, &(&examples, extensions))
+        Script::VisitExamplesEnd.call_with_descriptor(None, output, &(&examples, extensions))
     } else {
         Ok(())
     }
@@ -1029,24 +1471,26 @@ pub fn visit_examples(
 
 pub fn visit_request_bodies(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     request_bodies: &IndexMap<String, ReferenceOr<RequestBody>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !request_bodies.is_empty() {
-        Script::VisitRequestBodiesStart.call_with_descriptor(
+        let directive = Script::VisitRequestBodiesStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(request_bodies, extensions),
         )?;
-
-        for it in request_bodies {
-            visit_request_body(parsed_spec, out_path, Some(it.0), it.1)?;
+        if directive != VisitDirective::SkipChildren {
+            for it in request_bodies {
+                visit_located(parsed_spec, &["requestBodies", it.0], || {
+                    visit_request_body(parsed_spec, output, Some(it.0), it.1)
+                })?;
+            }
         }
-
         Script::VisitRequestBodiesEnd.call_with_descriptor(
             None,
-            out_path,
+            output,
             &(request_bodies, extensions),
         )
     } else {
@@ -1055,24 +1499,24 @@ pub fn visit_request_bodies(
 }
 
 pub fn visit_generic_parameters(
-    out_path: &Path,
+    output: &VisitOutput,
     parameters: &IndexMap<String, serde_json::Value>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !parameters.is_empty() {
-        Script::VisitGenericParametersStart.call_with_descriptor(
+        let directive = Script::VisitGenericParametersStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(&parameters, extensions),
         )?;
-
-        for it in parameters {
-            visit_generic_parameter(out_path, it.0, it.1, extensions)?;
+        if directive != VisitDirective::SkipChildren {
+            for it in parameters {
+                visit_generic_parameter(output, it.0, it.1, extensions)?;
+            }
         }
-
         Script::VisitGenericParametersEnd.call_with_descriptor(
             None,
-            out_path,
+            output,
             &(&parameters, extensions),
         )
     } else {
@@ -1082,16 +1526,16 @@ pub fn visit_generic_parameters(
 
 pub fn visit_header(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     header_name: Option<&str>,
     header: &ReferenceOr<Header>,
 ) -> Result<()> {
     match header {
         ReferenceOr::Reference { reference } => {
             let header = references::resolve_reference::<Header>(reference, parsed_spec)?;
-            Script::VisitHeaderReferenceStart.call_with_descriptor(
+            let directive = Script::VisitHeaderReferenceStart.call_with_directive(
                 get_call_id(header_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     &header_name,
                     reference,
@@ -1099,13 +1543,17 @@ pub fn visit_header(
                         .as_item()
                         .expect("Unable to get extensions from resolved header reference")
                         .extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
                 ),
             )?;
-
-            visit_header(parsed_spec, out_path, None, header)?;
+            if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_header(parsed_spec, output, None, &header)
+                })?;
+            }
             Script::VisitHeaderReferenceEnd.call_with_descriptor(
                 get_call_id(header_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     &header_name,
                     reference,
@@ -1117,27 +1565,27 @@ pub fn visit_header(
             )
         }
         ReferenceOr::Item(header) => {
-            Script::VisitHeaderStart.call_with_descriptor(
+            let directive = Script::VisitHeaderStart.call_with_directive(
                 header_name,
-                out_path,
+                output,
                 &(&header_name, &header, &header.extensions),
             )?;
+            if directive != VisitDirective::SkipChildren {
+                visit_parameter_schema_or_content(
+                    parsed_spec,
+                    output,
+                    None,
+                    &header.format,
+                    &header.extensions,
+                )?;
 
-            visit_parameter_schema_or_content(
-                parsed_spec,
-                out_path,
-                None,
-                &header.format,
-                &header.extensions,
-            )?;
-
-            visit_generic_example(out_path, &header.example, &header.extensions)?;
-
-            visit_examples(parsed_spec, out_path, &header.examples, &header.extensions)?;
+                visit_generic_example(output, &header.example, &header.extensions)?;
 
+                visit_examples(parsed_spec, output, &header.examples, &header.extensions)?;
+            }
             Script::VisitHeaderEnd.call_with_descriptor(
                 header_name,
-                out_path,
+                output,
                 &(&header_name, &header, &header.extensions),
             )
         }
@@ -1146,16 +1594,16 @@ pub fn visit_header(
 
 pub fn visit_security_scheme(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     scheme_name: Option<&str>,
     security_scheme: &ReferenceOr<SecurityScheme>,
 ) -> Result<()> {
     match security_scheme {
         ReferenceOr::Reference { reference } => {
             let scheme = references::resolve_reference::<SecurityScheme>(reference, parsed_spec)?;
-            Script::VisitSecuritySchemeReferenceStart.call_with_descriptor(
+            let directive = Script::VisitSecuritySchemeReferenceStart.call_with_directive(
                 get_call_id(scheme_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     &scheme_name,
                     reference,
@@ -1168,14 +1616,17 @@ pub fn visit_security_scheme(
                         SecurityScheme::OAuth2 { extensions, .. } => extensions,
                         SecurityScheme::OpenIDConnect { extensions, .. } => extensions,
                     },
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
                 ),
             )?;
-
-            visit_security_scheme(parsed_spec, out_path, None, scheme)?;
-
+            if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_security_scheme(parsed_spec, output, None, &scheme)
+                })?;
+            }
             Script::VisitSecuritySchemeReferenceEnd.call_with_descriptor(
                 get_call_id(scheme_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     &scheme_name,
                     reference,
@@ -1193,19 +1644,19 @@ pub fn visit_security_scheme(
         }
         ReferenceOr::Item(security_scheme) => match security_scheme {
             SecurityScheme::APIKey { .. } => {
-                visit_security_scheme_apikey(out_path, scheme_name, security_scheme)
+                visit_security_scheme_apikey(output, scheme_name, security_scheme)
             }
 
             SecurityScheme::HTTP { .. } => {
-                visit_security_scheme_http(out_path, scheme_name, security_scheme)
+                visit_security_scheme_http(output, scheme_name, security_scheme)
             }
 
             SecurityScheme::OAuth2 { .. } => {
-                visit_security_scheme_oauth2(out_path, scheme_name, security_scheme)
+                visit_security_scheme_oauth2(output, scheme_name, security_scheme)
             }
 
             SecurityScheme::OpenIDConnect { .. } => {
-                visit_security_scheme_openid_connect(out_path, scheme_name, security_scheme)
+                visit_security_scheme_openid_connect(output, scheme_name, security_scheme)
             }
         },
     }
@@ -1213,18 +1664,21 @@ pub fn visit_security_scheme(
 
 pub fn visit_headers(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     headers: &IndexMap<String, ReferenceOr<Header>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !headers.is_empty() {
-        Script::VisitHeadersStart.call_with_descriptor(None, out_path, &(headers, extensions))?;
-
-        for it in headers {
-            visit_header(parsed_spec, out_path, Some(it.0), it.1)?;
+        let directive =
+            Script::VisitHeadersStart.call_with_directive(None, output, &(headers, extensions))?;
+        if directive != VisitDirective::SkipChildren {
+            for it in headers {
+                visit_located(parsed_spec, &["headers", it.0], || {
+                    visit_header(parsed_spec, output, Some(it.0), it.1)
+                })?;
+            }
         }
-
-        Script::VisitHeadersEnd.call_with_descriptor(None, out_path, &(headers, extensions))
+        Script::VisitHeadersEnd.call_with_descriptor(None, output, &(headers, extensions))
     } else {
         Ok(())
     }
@@ -1232,24 +1686,26 @@ pub fn visit_headers(
 
 pub fn visit_security_schemes(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     security_schemes: &IndexMap<String, ReferenceOr<SecurityScheme>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !security_schemes.is_empty() {
-        Script::VisitSecuritySchemesStart.call_with_descriptor(
+        let directive = Script::VisitSecuritySchemesStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(&security_schemes, extensions),
         )?;
-
-        for it in security_schemes {
-            visit_security_scheme(parsed_spec, out_path, Some(it.0), it.1)?;
+        if directive != VisitDirective::SkipChildren {
+            for it in security_schemes {
+                visit_located(parsed_spec, &["securitySchemes", it.0], || {
+                    visit_security_scheme(parsed_spec, output, Some(it.0), it.1)
+                })?;
+            }
         }
-
         Script::VisitSecuritySchemesEnd.call_with_descriptor(
             None,
-            out_path,
+            output,
             &(&security_schemes, extensions),
         )
     } else {
@@ -1258,52 +1714,53 @@ pub fn visit_security_schemes(
 }
 
 pub fn visit_spec_tags(
-    out_path: &Path,
+    output: &VisitOutput,
     tags: &Vec<Tag>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !tags.is_empty() {
-        Script::VisitSpecTagsStart.call_with_descriptor(None, out_path, &(tags, extensions))?;
-
-        for tag in tags {
-            visit_external_docs(out_path, &tag.external_docs)?;
-            Script::VisitSpecTag.call_with_descriptor(
-                Some(&tag.name),
-                out_path,
-                &(tag, &tag.extensions),
-            )?;
+        let directive =
+            Script::VisitSpecTagsStart.call_with_directive(None, output, &(tags, extensions))?;
+        if directive != VisitDirective::SkipChildren {
+            for tag in tags {
+                visit_external_docs(output, &tag.external_docs)?;
+                Script::VisitSpecTag.call_with_descriptor(
+                    Some(&tag.name),
+                    output,
+                    &(tag, &tag.extensions),
+                )?;
+            }
         }
-
-        Script::VisitSpecTagsEnd.call_with_descriptor(None, out_path, &(tags, extensions))
+        Script::VisitSpecTagsEnd.call_with_descriptor(None, output, &(tags, extensions))
     } else {
         Ok(())
     }
 }
 
 pub fn visit_security_requirements(
-    out_path: &Path,
+    output: &VisitOutput,
     securities: &Option<Vec<SecurityRequirement>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if let Some(it) = securities.as_ref() {
         if !it.is_empty() {
-            Script::VisitSecurityRequirementsStart.call_with_descriptor(
+            let directive = Script::VisitSecurityRequirementsStart.call_with_directive(
                 None,
-                out_path,
+                output,
                 &(it, extensions),
             )?;
-
-            for sec_map in it {
-                Script::VisitSecurityRequirement.call_with_descriptor(
-                    None,
-                    out_path,
-                    &(sec_map, extensions),
-                )?;
+            if directive != VisitDirective::SkipChildren {
+                for sec_map in it {
+                    Script::VisitSecurityRequirement.call_with_descriptor(
+                        None,
+                        output,
+                        &(sec_map, extensions),
+                    )?;
+                }
             }
-
             Script::VisitSecurityRequirementsEnd.call_with_descriptor(
                 None,
-                out_path,
+                output,
                 &(it, extensions),
             )
         } else {
@@ -1314,33 +1771,44 @@ pub fn visit_security_requirements(
     }
 }
 pub fn visit_external_docs(
-    out_path: &Path,
+    output: &VisitOutput,
     external_docs: &Option<ExternalDocumentation>,
 ) -> Result<()> {
     if let Some(it) = external_docs {
-        Script::VisitExternalDocs.call_with_descriptor(
-            Some(&it.url),
-            out_path,
-            &(it, &it.extensions),
-        )
+        Script::VisitExternalDocs.call_with_descriptor(Some(&it.url), output, &(it, &it.extensions))
     } else {
         Ok(())
     }
 }
 pub fn visit_schemas(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     schemas: &IndexMap<String, ReferenceOr<Schema>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !schemas.is_empty() {
-        Script::VisitSchemasStart.call_with_descriptor(None, out_path, &(schemas, extensions))?;
-
-        for (schema_name, schema_ref) in schemas {
-            visit_schema(parsed_spec, out_path, Some(schema_name), schema_ref)?;
+        let directive =
+            Script::VisitSchemasStart.call_with_directive(None, output, &(schemas, extensions))?;
+        if directive != VisitDirective::SkipChildren {
+            for (schema_name, schema_ref) in schemas {
+                if !filters::is_schema_included(parsed_spec, schema_name) {
+                    continue;
+                }
+                let component_reference = references::absolute_reference(
+                    &spec_keys::get_schema_reference(schema_name),
+                    parsed_spec,
+                )?;
+                let entered = parsed_spec.enter_reference(&component_reference);
+                let visited = visit_located(parsed_spec, &["schemas", schema_name], || {
+                    visit_schema(parsed_spec, output, Some(schema_name), schema_ref)
+                });
+                if entered {
+                    parsed_spec.leave_reference();
+                }
+                visited?;
+            }
         }
-
-        Script::VisitSchemasEnd.call_with_descriptor(None, out_path, &(schemas, extensions))
+        Script::VisitSchemasEnd.call_with_descriptor(None, output, &(schemas, extensions))
     } else {
         Ok(())
     }
@@ -1348,22 +1816,24 @@ pub fn visit_schemas(
 
 pub fn visit_responses(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     responses: &IndexMap<String, ReferenceOr<Response>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !responses.is_empty() {
-        Script::VisitResponsesStart.call_with_descriptor(
+        let directive = Script::VisitResponsesStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(responses, extensions),
         )?;
-
-        for (response_name, response_ref) in responses {
-            visit_response(parsed_spec, out_path, Some(response_name), response_ref)?;
+        if directive != VisitDirective::SkipChildren {
+            for (response_name, response_ref) in responses {
+                visit_located(parsed_spec, &["responses", response_name], || {
+                    visit_response(parsed_spec, output, Some(response_name), response_ref)
+                })?;
+            }
         }
-
-        Script::VisitResponsesEnd.call_with_descriptor(None, out_path, &(responses, extensions))
+        Script::VisitResponsesEnd.call_with_descriptor(None, output, &(responses, extensions))
     } else {
         Ok(())
     }
@@ -1371,75 +1841,131 @@ pub fn visit_responses(
 
 pub fn visit_operation_responses(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     responses: &Responses,
 ) -> Result<()> {
-    Script::VisitOperationResponsesStart.call_with_descriptor(
+    let directive = Script::VisitOperationResponsesStart.call_with_directive(
         None,
-        out_path,
+        output,
         &(&responses, &responses.extensions),
     )?;
-    if let Some(response) = &responses.default {
-        visit_response(parsed_spec, out_path, None, response)?;
-    }
-
-    let converted: IndexMap<String, ReferenceOr<Response>> = responses
-        .responses
-        .iter()
-        .map(|(key, value)| (key.to_string(), value.clone()))
-        .collect();
+    if directive != VisitDirective::SkipChildren {
+        if let Some(response) = &responses.default {
+            visit_located(parsed_spec, &["responses", "default"], || {
+                visit_response(parsed_spec, output, None, response)
+            })?;
+        }
 
-    visit_responses(parsed_spec, out_path, &converted, &responses.extensions)?;
+        let converted: IndexMap<String, ReferenceOr<Response>> = responses
+            .responses
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
 
+        visit_responses(parsed_spec, output, &converted, &responses.extensions)?;
+    }
     Script::VisitOperationResponsesEnd.call_with_descriptor(
         None,
-        out_path,
+        output,
         &(&responses, &responses.extensions),
     )
 }
 
 pub fn visit_parameters(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     parameters: &IndexMap<String, ReferenceOr<Parameter>>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !parameters.is_empty() {
-        Script::VisitParametersStart.call_with_descriptor(
+        let directive = Script::VisitParametersStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(parameters, &extensions),
         )?;
+        if directive != VisitDirective::SkipChildren {
+            for (parameter_name, parameter_ref) in parameters {
+                visit_located(parsed_spec, &["parameters", parameter_name], || {
+                    visit_parameter(
+                        parsed_spec,
+                        output,
+                        Some(parameter_name),
+                        parameter_ref,
+                        extensions,
+                    )
+                })?;
+            }
+        }
+        Script::VisitParametersEnd.call_with_descriptor(None, output, &(parameters, &extensions))
+    } else {
+        Ok(())
+    }
+}
 
-        for (parameter_name, parameter_ref) in parameters {
-            visit_parameter(
-                parsed_spec,
-                out_path,
-                Some(parameter_name),
-                parameter_ref,
-                extensions,
-            )?;
+pub fn visit_paths(parsed_spec: &ParsedSpec, output: &VisitOutput, paths: &Paths) -> Result<()> {
+    if !paths.paths.is_empty() {
+        let directive = Script::VisitPathsStart.call_with_directive(
+            None,
+            output,
+            &(&paths, &paths.extensions),
+        )?;
+        if directive != VisitDirective::SkipChildren {
+            for it in &paths.paths {
+                visit_located(parsed_spec, &["paths", it.0], || {
+                    if filters::is_excluded(parsed_spec) {
+                        return Ok(());
+                    }
+                    visit_path_item_ref(parsed_spec, output, Some(it.0), it.1)
+                })?;
+            }
         }
-
-        Script::VisitParametersEnd.call_with_descriptor(None, out_path, &(parameters, &extensions))
+        Script::VisitPathsEnd.call_with_descriptor(None, output, &(&paths, &paths.extensions))
     } else {
         Ok(())
     }
 }
 
-pub fn visit_paths(parsed_spec: &ParsedSpec, out_path: &Path, paths: &Paths) -> Result<()> {
-    if !paths.paths.is_empty() {
-        Script::VisitPathsStart.call_with_descriptor(
+/// Webhooks of OpenAPI 3.1 are path items of requests initiated by API provider
+pub fn visit_webhooks(
+    parsed_spec: &ParsedSpec,
+    output: &VisitOutput,
+    webhooks: &Webhooks,
+    extensions: &IndexMap<String, serde_json::Value>,
+) -> Result<()> {
+    if !webhooks.is_empty() {
+        let directive = Script::VisitWebhooksStart.call_with_directive(
             None,
-            out_path,
-            &(&paths, &paths.extensions),
+            output,
+            &(webhooks, extensions),
         )?;
-
-        for it in &paths.paths {
-            visit_path_item_ref(parsed_spec, out_path, Some(it.0), it.1)?;
+        if directive != VisitDirective::SkipChildren {
+            for (webhook_name, path_item_ref) in webhooks {
+                visit_located(parsed_spec, &[WEBHOOKS_KEY, webhook_name], || {
+                    if filters::is_excluded(parsed_spec) {
+                        return Ok(());
+                    }
+                    let directive = Script::VisitWebhookStart.call_with_directive(
+                        Some(webhook_name),
+                        output,
+                        &(webhook_name, path_item_ref, extensions),
+                    )?;
+                    if directive != VisitDirective::SkipChildren {
+                        visit_path_item_ref(
+                            parsed_spec,
+                            output,
+                            Some(webhook_name),
+                            path_item_ref,
+                        )?;
+                    }
+                    Script::VisitWebhookEnd.call_with_descriptor(
+                        Some(webhook_name),
+                        output,
+                        &(webhook_name, path_item_ref, extensions),
+                    )
+                })?;
+            }
         }
-
-        Script::VisitPathsEnd.call_with_descriptor(None, out_path, &(&paths, &paths.extensions))
+        Script::VisitWebhooksEnd.call_with_descriptor(None, output, &(webhooks, extensions))
     } else {
         Ok(())
     }
@@ -1447,16 +1973,16 @@ pub fn visit_paths(parsed_spec: &ParsedSpec, out_path: &Path, paths: &Paths) ->
 
 pub fn visit_path_item_ref(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     path_item_name: Option<&str>,
     path_item_ref: &ReferenceOr<PathItem>,
 ) -> Result<()> {
     match path_item_ref {
         ReferenceOr::Reference { reference } => {
             let path_item = references::resolve_reference::<PathItem>(reference, parsed_spec)?;
-            Script::VisitPathItemReferenceStart.call_with_descriptor(
+            let directive = Script::VisitPathItemReferenceStart.call_with_directive(
                 get_call_id(path_item_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     &path_item_name,
                     reference,
@@ -1464,14 +1990,17 @@ pub fn visit_path_item_ref(
                         .as_item()
                         .expect("Unable to get extensions from resolved path item reference")
                         .extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
                 ),
             )?;
-
-            visit_path_item_ref(parsed_spec, out_path, None, path_item)?;
-
+            if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_path_item_ref(parsed_spec, output, None, &path_item)
+                })?;
+            }
             Script::VisitPathItemReferenceEnd.call_with_descriptor(
                 get_call_id(path_item_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     &path_item_name,
                     reference,
@@ -1483,7 +2012,7 @@ pub fn visit_path_item_ref(
             )
         }
         ReferenceOr::Item(path_item) => {
-            visit_path_item(parsed_spec, out_path, path_item_name, path_item)
+            visit_path_item(parsed_spec, output, path_item_name, path_item)
         }
     }
 }
@@ -1491,7 +2020,7 @@ pub fn visit_path_item_ref(
 //TODO: check, may be need switch from external extensions to Parameter.parameter_data.extensions
 pub fn visit_parameter(
     parsed_spec: &ParsedSpec, /*  */
-    out_path: &Path,
+    output: &VisitOutput,
     parameter_name: Option<&str>,
     parameter_ref: &ReferenceOr<Parameter>,
     extensions: &IndexMap<String, serde_json::Value>,
@@ -1499,46 +2028,53 @@ pub fn visit_parameter(
     match parameter_ref {
         ReferenceOr::Reference { reference } => {
             let parameter = references::resolve_reference::<Parameter>(reference, parsed_spec)?;
-            Script::VisitParameterReferenceStart.call_with_descriptor(
+            let directive = Script::VisitParameterReferenceStart.call_with_directive(
                 get_call_id(parameter_name, reference).as_deref(),
-                out_path,
-                &(&parameter_name, reference, extensions),
+                output,
+                &(
+                    &parameter_name,
+                    reference,
+                    extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
+                ),
             )?;
-
-            visit_parameter(parsed_spec, out_path, None, parameter, extensions)?;
-
+            if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_parameter(parsed_spec, output, None, &parameter, extensions)
+                })?;
+            }
             Script::VisitParameterReferenceEnd.call_with_descriptor(
                 get_call_id(parameter_name, reference).as_deref(),
-                out_path,
+                output,
                 &(&parameter_name, reference, extensions),
             )
         }
         ReferenceOr::Item(parameter) => match parameter {
             Parameter::Query { .. } => {
-                visit_query_parameter(parsed_spec, out_path, parameter_name, parameter, extensions)
+                visit_query_parameter(parsed_spec, output, parameter_name, parameter, extensions)
             }
             Parameter::Header { .. } => {
-                visit_header_parameter(parsed_spec, out_path, parameter_name, parameter, extensions)
+                visit_header_parameter(parsed_spec, output, parameter_name, parameter, extensions)
             }
             Parameter::Path { .. } => {
-                visit_path_parameter(parsed_spec, out_path, parameter_name, parameter, extensions)
+                visit_path_parameter(parsed_spec, output, parameter_name, parameter, extensions)
             }
             Parameter::Cookie { .. } => {
-                visit_cookie_parameter(parsed_spec, out_path, parameter_name, parameter, extensions)
+                visit_cookie_parameter(parsed_spec, output, parameter_name, parameter, extensions)
             }
         },
     }
 }
 
 pub fn visit_security_scheme_http(
-    out_path: &Path,
+    output: &VisitOutput,
     scheme_name: Option<&str>,
     http: &SecurityScheme,
 ) -> Result<()> {
     if let SecurityScheme::HTTP { extensions, .. } = http {
         Script::VisitSecuritySchemeHttp.call_with_descriptor(
             scheme_name,
-            out_path,
+            output,
             &(&scheme_name, &http, &extensions),
         )
     } else {
@@ -1547,7 +2083,7 @@ pub fn visit_security_scheme_http(
 }
 
 pub fn visit_security_scheme_oauth2(
-    out_path: &Path,
+    output: &VisitOutput,
     scheme_name: Option<&str>,
     oauth2: &SecurityScheme,
 ) -> Result<()> {
@@ -1555,16 +2091,17 @@ pub fn visit_security_scheme_oauth2(
         flows, extensions, ..
     } = oauth2
     {
-        Script::VisitSecuritySchemeOAuth2Start.call_with_descriptor(
+        let directive = Script::VisitSecuritySchemeOAuth2Start.call_with_directive(
             scheme_name,
-            out_path,
+            output,
             &(&scheme_name, &oauth2, extensions),
         )?;
-        visit_security_scheme_oauth2_flows(out_path, flows)?;
-
+        if directive != VisitDirective::SkipChildren {
+            visit_security_scheme_oauth2_flows(output, flows)?;
+        }
         Script::VisitSecuritySchemeOAuth2End.call_with_descriptor(
             scheme_name,
-            out_path,
+            output,
             &(&scheme_name, &oauth2, extensions),
         )
     } else {
@@ -1572,35 +2109,36 @@ pub fn visit_security_scheme_oauth2(
     }
 }
 
-pub fn visit_security_scheme_oauth2_flows(out_path: &Path, flows: &OAuth2Flows) -> Result<()> {
-    Script::VisitSecuritySchemeOAuth2FlowsStart.call_with_descriptor(
+pub fn visit_security_scheme_oauth2_flows(output: &VisitOutput, flows: &OAuth2Flows) -> Result<()> {
+    let directive = Script::VisitSecuritySchemeOAuth2FlowsStart.call_with_directive(
         None,
-        out_path,
+        output,
         &(flows, &flows.extensions),
     )?;
-    visit_security_scheme_oauth2_flows_implicit(out_path, &flows.implicit)?;
+    if directive != VisitDirective::SkipChildren {
+        visit_security_scheme_oauth2_flows_implicit(output, &flows.implicit)?;
 
-    visit_security_scheme_oauth2_flows_password(out_path, &flows.password)?;
+        visit_security_scheme_oauth2_flows_password(output, &flows.password)?;
 
-    visit_security_scheme_oauth2_flows_client_credentials(out_path, &flows.client_credentials)?;
-
-    visit_security_scheme_oauth2_flows_authorization_code(out_path, &flows.authorization_code)?;
+        visit_security_scheme_oauth2_flows_client_credentials(output, &flows.client_credentials)?;
 
+        visit_security_scheme_oauth2_flows_authorization_code(output, &flows.authorization_code)?;
+    }
     Script::VisitSecuritySchemeOAuth2FlowsEnd.call_with_descriptor(
         None,
-        out_path,
+        output,
         &(flows, &flows.extensions),
     )
 }
 
 pub fn visit_security_scheme_oauth2_flows_implicit(
-    out_path: &Path,
+    output: &VisitOutput,
     flow: &Option<ImplicitOAuth2Flow>,
 ) -> Result<()> {
     if let Some(flow) = flow {
         Script::VisitSecuritySchemeOAuth2FlowImplicit.call_with_descriptor(
             None,
-            out_path,
+            output,
             &(flow, &flow.extensions),
         )
     } else {
@@ -1609,13 +2147,13 @@ pub fn visit_security_scheme_oauth2_flows_implicit(
 }
 
 pub fn visit_security_scheme_oauth2_flows_password(
-    out_path: &Path,
+    output: &VisitOutput,
     flow: &Option<PasswordOAuth2Flow>,
 ) -> Result<()> {
     if let Some(flow) = flow {
         Script::VisitSecuritySchemeOAuth2FlowPassword.call_with_descriptor(
             None,
-            out_path,
+            output,
             &(flow, &flow.extensions),
         )
     } else {
@@ -1624,13 +2162,13 @@ pub fn visit_security_scheme_oauth2_flows_password(
 }
 
 pub fn visit_security_scheme_oauth2_flows_client_credentials(
-    out_path: &Path,
+    output: &VisitOutput,
     flow: &Option<ClientCredentialsOAuth2Flow>,
 ) -> Result<()> {
     if let Some(flow) = flow {
         Script::VisitSecuritySchemeOAuth2FlowClientCredentials.call_with_descriptor(
             None,
-            out_path,
+            output,
             &(flow, &flow.extensions),
         )
     } else {
@@ -1639,13 +2177,13 @@ pub fn visit_security_scheme_oauth2_flows_client_credentials(
 }
 
 pub fn visit_security_scheme_oauth2_flows_authorization_code(
-    out_path: &Path,
+    output: &VisitOutput,
     flow: &Option<AuthorizationCodeOAuth2Flow>,
 ) -> Result<()> {
     if let Some(flow) = flow {
         Script::VisitSecuritySchemeOAuth2FlowAuthorizationCode.call_with_descriptor(
             None,
-            out_path,
+            output,
             &(flow, &flow.extensions),
         )
     } else {
@@ -1654,14 +2192,14 @@ pub fn visit_security_scheme_oauth2_flows_authorization_code(
 }
 
 pub fn visit_security_scheme_apikey(
-    out_path: &Path,
+    output: &VisitOutput,
     scheme_name: Option<&str>,
     api_key: &SecurityScheme,
 ) -> Result<()> {
     if let SecurityScheme::APIKey { extensions, .. } = api_key {
         Script::VisitSecuritySchemeApiKey.call_with_descriptor(
             scheme_name,
-            out_path,
+            output,
             &(scheme_name, &api_key, &extensions),
         )
     } else {
@@ -1670,14 +2208,14 @@ pub fn visit_security_scheme_apikey(
 }
 
 pub fn visit_security_scheme_openid_connect(
-    out_path: &Path,
+    output: &VisitOutput,
     scheme_name: Option<&str>,
     openid_connect: &SecurityScheme,
 ) -> Result<()> {
     if let SecurityScheme::OpenIDConnect { extensions, .. } = openid_connect {
         Script::VisitSecuritySchemeOpenIdConnect.call_with_descriptor(
             scheme_name,
-            out_path,
+            output,
             &(scheme_name, &openid_connect, &extensions),
         )
     } else {
@@ -1687,61 +2225,58 @@ pub fn visit_security_scheme_openid_connect(
 
 pub fn visit_parameter_data(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     parameter_data: &ParameterData,
 ) -> Result<()> {
-    Script::VisitParameterDataStart.call_with_descriptor(
+    let directive = Script::VisitParameterDataStart.call_with_directive(
         Some(&parameter_data.name),
-        out_path,
+        output,
         &(parameter_data, &parameter_data.extensions),
     )?;
-    visit_parameter_schema_or_content(
-        parsed_spec,
-        out_path,
-        None,
-        &parameter_data.format,
-        &parameter_data.extensions,
-    )?;
-
-    visit_generic_example(
-        out_path,
-        &parameter_data.example,
-        &parameter_data.extensions,
-    )?;
+    if directive != VisitDirective::SkipChildren {
+        visit_parameter_schema_or_content(
+            parsed_spec,
+            output,
+            None,
+            &parameter_data.format,
+            &parameter_data.extensions,
+        )?;
 
-    visit_examples(
-        parsed_spec,
-        out_path,
-        &parameter_data.examples,
-        &parameter_data.extensions,
-    )?;
+        visit_generic_example(output, &parameter_data.example, &parameter_data.extensions)?;
 
+        visit_examples(
+            parsed_spec,
+            output,
+            &parameter_data.examples,
+            &parameter_data.extensions,
+        )?;
+    }
     Script::VisitParameterDataEnd.call_with_descriptor(
         Some(&parameter_data.name),
-        out_path,
+        output,
         &(parameter_data, &parameter_data.extensions),
     )
 }
 
 pub fn visit_query_parameter(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     parameter_name: Option<&str>,
     parameter: &Parameter,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if let Parameter::Query { parameter_data, .. } = parameter {
-        Script::VisitQueryParameterStart.call_with_descriptor(
+        let directive = Script::VisitQueryParameterStart.call_with_directive(
             parameter_name,
-            out_path,
+            output,
             &(parameter_name, &parameter, &extensions),
         )?;
-
-        visit_parameter_data(parsed_spec, out_path, parameter_data)?;
-
+        if directive != VisitDirective::SkipChildren {
+            visit_parameter_data(parsed_spec, output, parameter_data)?;
+        }
         Script::VisitQueryParameterEnd.call_with_descriptor(
             parameter_name,
-            out_path,
+            output,
             &(parameter_name, &parameter, &extensions),
         )
     } else {
@@ -1751,23 +2286,23 @@ pub fn visit_query_parameter(
 
 pub fn visit_header_parameter(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     parameter_name: Option<&str>,
     parameter: &Parameter,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if let Parameter::Header { parameter_data, .. } = parameter {
-        Script::VisitHeaderParameterStart.call_with_descriptor(
+        let directive = Script::VisitHeaderParameterStart.call_with_directive(
             parameter_name,
-            out_path,
+            output,
             &(parameter_name, &parameter, &extensions),
         )?;
-
-        visit_parameter_data(parsed_spec, out_path, parameter_data)?;
-
+        if directive != VisitDirective::SkipChildren {
+            visit_parameter_data(parsed_spec, output, parameter_data)?;
+        }
         Script::VisitHeaderParameterEnd.call_with_descriptor(
             parameter_name,
-            out_path,
+            output,
             &(parameter_name, &parameter, &extensions),
         )
     } else {
@@ -1777,23 +2312,23 @@ pub fn visit_header_parameter(
 
 pub fn visit_path_parameter(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     parameter_name: Option<&str>,
     parameter: &Parameter,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if let Parameter::Path { parameter_data, .. } = parameter {
-        Script::VisitPathParameterStart.call_with_descriptor(
+        let directive = Script::VisitPathParameterStart.call_with_directive(
             parameter_name,
-            out_path,
+            output,
             &(parameter_name, &parameter, &extensions),
         )?;
-
-        visit_parameter_data(parsed_spec, out_path, parameter_data)?;
-
+        if directive != VisitDirective::SkipChildren {
+            visit_parameter_data(parsed_spec, output, parameter_data)?;
+        }
         Script::VisitPathParameterEnd.call_with_descriptor(
             parameter_name,
-            out_path,
+            output,
             &(parameter_name, &parameter, &extensions),
         )
     } else {
@@ -1803,178 +2338,215 @@ pub fn visit_path_parameter(
 
 pub fn visit_path_item(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     path_item_name: Option<&str>,
     path_item: &PathItem,
 ) -> Result<()> {
-    Script::VisitPathItemStart.call_with_descriptor(
+    // Path item of webhook or path item behind reference is checked at its own location
+    if filters::is_excluded(parsed_spec) {
+        return Ok(());
+    }
+    let directive = Script::VisitPathItemStart.call_with_directive(
         path_item_name,
-        out_path,
+        output,
         &(path_item_name, &path_item, &path_item.extensions),
     )?;
-    visit_operation(
-        parsed_spec,
-        out_path,
-        &path_item.trace,
-        &BracketScripts {
-            start: Script::VisitTraceOperationStart,
-            end: Script::VisitTraceOperationEnd,
-        },
-    )?;
-
-    visit_operation(
-        parsed_spec,
-        out_path,
-        &path_item.put,
-        &BracketScripts {
-            start: Script::VisitPutOperationStart,
-            end: Script::VisitPutOperationEnd,
-        },
-    )?;
+    if directive != VisitDirective::SkipChildren {
+        visit_located(parsed_spec, &["trace"], || {
+            visit_operation(
+                parsed_spec,
+                output,
+                &path_item.trace,
+                &path_item.parameters,
+                &BracketScripts {
+                    start: Script::VisitTraceOperationStart,
+                    end: Script::VisitTraceOperationEnd,
+                },
+            )
+        })?;
 
-    visit_operation(
-        parsed_spec,
-        out_path,
-        &path_item.post,
-        &BracketScripts {
-            start: Script::VisitPostOperationStart,
-            end: Script::VisitPostOperationEnd,
-        },
-    )?;
+        visit_located(parsed_spec, &["put"], || {
+            visit_operation(
+                parsed_spec,
+                output,
+                &path_item.put,
+                &path_item.parameters,
+                &BracketScripts {
+                    start: Script::VisitPutOperationStart,
+                    end: Script::VisitPutOperationEnd,
+                },
+            )
+        })?;
 
-    visit_operation(
-        parsed_spec,
-        out_path,
-        &path_item.patch,
-        &BracketScripts {
-            start: Script::VisitPatchOperationStart,
-            end: Script::VisitPatchOperationEnd,
-        },
-    )?;
+        visit_located(parsed_spec, &["post"], || {
+            visit_operation(
+                parsed_spec,
+                output,
+                &path_item.post,
+                &path_item.parameters,
+                &BracketScripts {
+                    start: Script::VisitPostOperationStart,
+                    end: Script::VisitPostOperationEnd,
+                },
+            )
+        })?;
 
-    visit_operation(
-        parsed_spec,
-        out_path,
-        &path_item.options,
-        &BracketScripts {
-            start: Script::VisitOptionsOperationStart,
-            end: Script::VisitOptionsOperationEnd,
-        },
-    )?;
+        visit_located(parsed_spec, &["patch"], || {
+            visit_operation(
+                parsed_spec,
+                output,
+                &path_item.patch,
+                &path_item.parameters,
+                &BracketScripts {
+                    start: Script::VisitPatchOperationStart,
+                    end: Script::VisitPatchOperationEnd,
+                },
+            )
+        })?;
 
-    visit_operation(
-        parsed_spec,
-        out_path,
-        &path_item.head,
-        &BracketScripts {
-            start: Script::VisitHeadOperationStart,
-            end: Script::VisitHeadOperationEnd,
-        },
-    )?;
+        visit_located(parsed_spec, &["options"], || {
+            visit_operation(
+                parsed_spec,
+                output,
+                &path_item.options,
+                &path_item.parameters,
+                &BracketScripts {
+                    start: Script::VisitOptionsOperationStart,
+                    end: Script::VisitOptionsOperationEnd,
+                },
+            )
+        })?;
 
-    visit_operation(
-        parsed_spec,
-        out_path,
-        &path_item.get,
-        &BracketScripts {
-            start: Script::VisitGetOperationStart,
-            end: Script::VisitGetOperationEnd,
-        },
-    )?;
+        visit_located(parsed_spec, &["head"], || {
+            visit_operation(
+                parsed_spec,
+                output,
+                &path_item.head,
+                &path_item.parameters,
+                &BracketScripts {
+                    start: Script::VisitHeadOperationStart,
+                    end: Script::VisitHeadOperationEnd,
+                },
+            )
+        })?;
 
-    visit_operation(
-        parsed_spec,
-        out_path,
-        &path_item.delete,
-        &BracketScripts {
-            start: Script::VisitDeleteOperationStart,
-            end: Script::VisitDeleteOperationEnd,
-        },
-    )?;
+        visit_located(parsed_spec, &["get"], || {
+            visit_operation(
+                parsed_spec,
+                output,
+                &path_item.get,
+                &path_item.parameters,
+                &BracketScripts {
+                    start: Script::VisitGetOperationStart,
+                    end: Script::VisitGetOperationEnd,
+                },
+            )
+        })?;
 
-    visit_servers(out_path, &path_item.servers, &path_item.extensions)?;
+        visit_located(parsed_spec, &["delete"], || {
+            visit_operation(
+                parsed_spec,
+                output,
+                &path_item.delete,
+                &path_item.parameters,
+                &BracketScripts {
+                    start: Script::VisitDeleteOperationStart,
+                    end: Script::VisitDeleteOperationEnd,
+                },
+            )
+        })?;
 
-    //Not so effective, but used existing visitor
-    visit_parameters(
-        parsed_spec,
-        out_path,
-        &path_item
-            .parameters
-            .iter()
-            .enumerate()
-            .map(|(index, value)| (index.to_string(), value.clone()))
-            .collect::<IndexMap<String, ReferenceOr<Parameter>>>(),
-        &path_item.extensions,
-    )?;
+        visit_servers(output, &path_item.servers, &path_item.extensions)?;
 
+        //Not so effective, but used existing visitor
+        visit_parameters(
+            parsed_spec,
+            output,
+            &path_item
+                .parameters
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (index.to_string(), value.clone()))
+                .collect::<IndexMap<String, ReferenceOr<Parameter>>>(),
+            &path_item.extensions,
+        )?;
+    }
     Script::VisitPathItemEnd.call_with_descriptor(
         path_item_name,
-        out_path,
+        output,
         &(path_item_name, &path_item, &path_item.extensions),
     )
 }
 
 pub fn visit_operation(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     operation: &Option<Operation>,
+    path_item_parameters: &[ReferenceOr<Parameter>],
     braced_scripts: &BracketScripts,
 ) -> Result<()> {
-    if let Some(operation) = operation {
-        braced_scripts.start.call_with_descriptor(
+    if let Some(operation) = operation
+        .as_ref()
+        .filter(|_| !filters::is_excluded(parsed_spec))
+    {
+        let effective_parameters =
+            get_effective_parameters(parsed_spec, path_item_parameters, &operation.parameters)?;
+        let directive = braced_scripts.start.call_with_directive(
             operation
                 .operation_id
                 .clone()
                 .or_else(|| operation.summary.clone())
                 .as_deref(),
-            out_path,
-            &(&operation, &operation.extensions),
-        )?;
-
-        visit_external_docs(out_path, &operation.external_docs)?;
-
-        visit_parameters(
-            parsed_spec,
-            out_path,
-            &operation
-                .parameters
-                .iter()
-                .enumerate()
-                .map(|(index, value)| (index.to_string(), value.clone()))
-                .collect::<IndexMap<String, ReferenceOr<Parameter>>>(),
-            &operation.extensions,
+            output,
+            &(&operation, &operation.extensions, &effective_parameters),
         )?;
+        if directive != VisitDirective::SkipChildren {
+            visit_external_docs(output, &operation.external_docs)?;
 
-        if let Some(request_body) = &operation.request_body {
-            visit_request_body(parsed_spec, out_path, None, request_body)?;
-        }
+            visit_parameters(
+                parsed_spec,
+                output,
+                &operation
+                    .parameters
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| (index.to_string(), value.clone()))
+                    .collect::<IndexMap<String, ReferenceOr<Parameter>>>(),
+                &operation.extensions,
+            )?;
 
-        visit_operation_responses(parsed_spec, out_path, &operation.responses)?;
+            if let Some(request_body) = &operation.request_body {
+                visit_located(parsed_spec, &["requestBody"], || {
+                    visit_request_body(parsed_spec, output, None, request_body)
+                })?;
+            }
 
-        let operation_callbacks: IndexMap<String, ReferenceOr<Callback>> = operation
-            .callbacks
-            .iter()
-            .map(|(key, value)| (key.clone(), ReferenceOr::Item(value.clone())))
-            .collect();
+            visit_operation_responses(parsed_spec, output, &operation.responses)?;
 
-        visit_callbacks(
-            parsed_spec,
-            out_path,
-            &operation_callbacks,
-            &operation.extensions,
-        )?;
+            let operation_callbacks: IndexMap<String, ReferenceOr<Callback>> = operation
+                .callbacks
+                .iter()
+                .map(|(key, value)| (key.clone(), ReferenceOr::Item(value.clone())))
+                .collect();
 
-        visit_security_requirements(out_path, &operation.security, &operation.extensions)?;
+            visit_callbacks(
+                parsed_spec,
+                output,
+                &operation_callbacks,
+                &operation.extensions,
+            )?;
 
-        visit_servers(out_path, &operation.servers, &operation.extensions)?;
+            visit_security_requirements(output, &operation.security, &operation.extensions)?;
 
+            visit_servers(output, &operation.servers, &operation.extensions)?;
+        }
         braced_scripts.end.call_with_descriptor(
             operation
                 .operation_id
                 .clone()
                 .or_else(|| operation.summary.clone())
                 .as_deref(),
-            out_path,
+            output,
             &(&operation, &operation.extensions),
         )
     } else {
@@ -1982,25 +2554,65 @@ pub fn visit_operation(
     }
 }
 
+/// Parameters of path item overridden by parameters of operation with the same name and location,
+/// references are resolved. Overriding parameter takes place of overridden one, others of operation
+/// follow parameters of path item.
+fn get_effective_parameters(
+    parsed_spec: &ParsedSpec,
+    path_item_parameters: &[ReferenceOr<Parameter>],
+    operation_parameters: &[ReferenceOr<Parameter>],
+) -> Result<Vec<Parameter>> {
+    let mut effective: Vec<Parameter> = Vec::new();
+    for parameter_ref in path_item_parameters.iter().chain(operation_parameters) {
+        let parameter = match parameter_ref {
+            ReferenceOr::Reference { reference } => {
+                references::resolve_reference::<Parameter>(reference, parsed_spec)?
+                    .as_item()
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Parameter [{}] is not resolved", reference))?
+            }
+            ReferenceOr::Item(parameter) => parameter.clone(),
+        };
+        match effective
+            .iter_mut()
+            .find(|it| get_parameter_key(it) == get_parameter_key(&parameter))
+        {
+            Some(overridden) => *overridden = parameter,
+            None => effective.push(parameter),
+        }
+    }
+    Ok(effective)
+}
+
+fn get_parameter_key(parameter: &Parameter) -> (&str, &str) {
+    let location = match parameter {
+        Parameter::Query { .. } => "query",
+        Parameter::Header { .. } => "header",
+        Parameter::Path { .. } => "path",
+        Parameter::Cookie { .. } => "cookie",
+    };
+    (location, &parameter.parameter_data_ref().name)
+}
+
 pub fn visit_cookie_parameter(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     parameter_name: Option<&str>,
     parameter: &Parameter,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if let Parameter::Cookie { parameter_data, .. } = parameter {
-        Script::VisitCookieParameterStart.call_with_descriptor(
+        let directive = Script::VisitCookieParameterStart.call_with_directive(
             parameter_name,
-            out_path,
+            output,
             &(parameter_name, &parameter, &extensions),
         )?;
-
-        visit_parameter_data(parsed_spec, out_path, parameter_data)?;
-
+        if directive != VisitDirective::SkipChildren {
+            visit_parameter_data(parsed_spec, output, parameter_data)?;
+        }
         Script::VisitCookieParameterEnd.call_with_descriptor(
             parameter_name,
-            out_path,
+            output,
             &(parameter_name, &parameter, &extensions),
         )
     } else {
@@ -2010,109 +2622,116 @@ pub fn visit_cookie_parameter(
 
 pub fn visit_spec_components(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     components: &Option<Components>,
 ) -> Result<()> {
     if let Some(components) = components {
-        Script::VisitComponentsStart.call_with_descriptor(
+        let directive = Script::VisitComponentsStart.call_with_directive(
             None,
-            out_path,
+            output,
             &(components, &components.extensions),
         )?;
+        if directive != VisitDirective::SkipChildren {
+            parsed_spec.enter_child_location(&["components"]);
 
-        visit_schemas(
-            parsed_spec,
-            out_path,
-            &components.schemas,
-            &components.extensions,
-        )?;
+            visit_schemas(
+                parsed_spec,
+                output,
+                &components.schemas,
+                &components.extensions,
+            )?;
 
-        visit_responses(
-            parsed_spec,
-            out_path,
-            &components.responses,
-            &components.extensions,
-        )?;
+            visit_responses(
+                parsed_spec,
+                output,
+                &components.responses,
+                &components.extensions,
+            )?;
 
-        visit_parameters(
-            parsed_spec,
-            out_path,
-            &components.parameters,
-            &components.extensions,
-        )?;
+            visit_parameters(
+                parsed_spec,
+                output,
+                &components.parameters,
+                &components.extensions,
+            )?;
 
-        visit_examples(
-            parsed_spec,
-            out_path,
-            &components.examples,
-            &components.extensions,
-        )?;
+            visit_examples(
+                parsed_spec,
+                output,
+                &components.examples,
+                &components.extensions,
+            )?;
 
-        visit_request_bodies(
-            parsed_spec,
-            out_path,
-            &components.request_bodies,
-            &components.extensions,
-        )?;
+            visit_request_bodies(
+                parsed_spec,
+                output,
+                &components.request_bodies,
+                &components.extensions,
+            )?;
 
-        visit_headers(
-            parsed_spec,
-            out_path,
-            &components.headers,
-            &components.extensions,
-        )?;
+            visit_headers(
+                parsed_spec,
+                output,
+                &components.headers,
+                &components.extensions,
+            )?;
 
-        visit_security_schemes(
-            parsed_spec,
-            out_path,
-            &components.security_schemes,
-            &components.extensions,
-        )?;
+            visit_security_schemes(
+                parsed_spec,
+                output,
+                &components.security_schemes,
+                &components.extensions,
+            )?;
 
-        visit_links(
-            parsed_spec,
-            out_path,
-            &components.links,
-            &components.extensions,
-        )?;
+            visit_links(
+                parsed_spec,
+                output,
+                &components.links,
+                &components.extensions,
+            )?;
 
-        visit_callbacks(
-            parsed_spec,
-            out_path,
-            &components.callbacks,
-            &components.extensions,
-        )?;
+            visit_callbacks(
+                parsed_spec,
+                output,
+                &components.callbacks,
+                &components.extensions,
+            )?;
 
-        Script::VisitComponentsEnd.call_with_descriptor(None, out_path, &(&components.extensions))
+            parsed_spec.leave_location();
+        }
+        Script::VisitComponentsEnd.call_with_descriptor(None, output, &(&components.extensions))
     } else {
         Ok(())
     }
 }
 
 pub fn visit_schema_default(
-    out_path: &Path,
+    output: &VisitOutput,
     default: &Option<serde_json::Value>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if let Some(default) = default.as_ref() {
-        Script::VisitDefault.call_with_descriptor(None, out_path, &(default, extensions))
+        Script::VisitDefault.call_with_descriptor(None, output, &(default, extensions))
     } else {
         Ok(())
     }
 }
 
-pub fn visit_spec_info(out_path: &Path, info: &Info) -> Result<()> {
-    Script::VisitSpecInfoStart.call_with_descriptor(None, out_path, &(info, &info.extensions))?;
-    visit_spec_info_contact(out_path, &info.contact)?;
-    visit_spec_info_license(out_path, &info.license)?;
-    Script::VisitSpecInfoEnd.call_with_descriptor(None, out_path, &(info, &info.extensions))
+pub fn visit_spec_info(output: &VisitOutput, info: &Info) -> Result<()> {
+    let directive =
+        Script::VisitSpecInfoStart.call_with_directive(None, output, &(info, &info.extensions))?;
+    if directive != VisitDirective::SkipChildren {
+        visit_spec_info_contact(output, &info.contact)?;
+        visit_spec_info_license(output, &info.license)?;
+    }
+    Script::VisitSpecInfoEnd.call_with_descriptor(None, output, &(info, &info.extensions))
 }
 
-pub fn visit_spec_info_contact(out_path: &Path, contact: &Option<Contact>) -> Result<()> {
+pub fn visit_spec_info_contact(output: &VisitOutput, contact: &Option<Contact>) -> Result<()> {
     if let Some(it) = contact {
         Script::VisitSpecInfoContact.call_with_descriptor(
             it.name.as_deref(),
-            out_path,
+            output,
             &(&it, &it.extensions),
         )
     } else {
@@ -2120,53 +2739,55 @@ pub fn visit_spec_info_contact(out_path: &Path, contact: &Option<Contact>) -> Re
     }
 }
 
-pub fn visit_spec_info_license(out_path: &Path, license: &Option<License>) -> Result<()> {
+pub fn visit_spec_info_license(output: &VisitOutput, license: &Option<License>) -> Result<()> {
     if let Some(it) = license {
         Script::VisitSpecInfoLicense.call_with_descriptor(
             Some(&it.name),
-            out_path,
+            output,
             &(&it, &it.extensions),
         )
     } else {
         Ok(())
     }
 }
-pub fn visit_server(out_path: &Path, server: &Server) -> Result<()> {
-    Script::VisitServerStart.call_with_descriptor(
+pub fn visit_server(output: &VisitOutput, server: &Server) -> Result<()> {
+    let directive = Script::VisitServerStart.call_with_directive(
         Some(&server.url),
-        out_path,
+        output,
         &(server, &server.extensions),
     )?;
-    if let Some(variables) = server.variables.as_ref() {
-        for it in variables {
-            Script::VisitServerVariable.call_with_descriptor(
-                Some(it.0),
-                out_path,
-                &(&server.url, &it.0, &it.1, &it.1.extensions),
-            )?;
+    if directive != VisitDirective::SkipChildren {
+        if let Some(variables) = server.variables.as_ref() {
+            for it in variables {
+                Script::VisitServerVariable.call_with_descriptor(
+                    Some(it.0),
+                    output,
+                    &(&server.url, &it.0, &it.1, &it.1.extensions),
+                )?;
+            }
         }
     }
-
     Script::VisitServerEnd.call_with_descriptor(
         Some(&server.url),
-        out_path,
+        output,
         &(server, &server.extensions),
     )
 }
 
 pub fn visit_servers(
-    out_path: &Path,
+    output: &VisitOutput,
     servers: &Vec<Server>,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
     if !servers.is_empty() {
-        Script::VisitServersStart.call_with_descriptor(None, out_path, &(servers, extensions))?;
-
-        for server in servers {
-            visit_server(out_path, server)?;
+        let directive =
+            Script::VisitServersStart.call_with_directive(None, output, &(servers, extensions))?;
+        if directive != VisitDirective::SkipChildren {
+            for server in servers {
+                visit_server(output, server)?;
+            }
         }
-
-        Script::VisitServersEnd.call_with_descriptor(None, out_path, &(servers, extensions))
+        Script::VisitServersEnd.call_with_descriptor(None, output, &(servers, extensions))
     } else {
         Ok(())
     }
@@ -2174,16 +2795,27 @@ pub fn visit_servers(
 
 pub fn visit_object_property(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     property_name: Option<&str>,
     property_schema_ref: &ReferenceOr<Schema>,
 ) -> Result<()> {
     match property_schema_ref {
         ReferenceOr::Reference { reference } => {
             let property_schema = references::resolve_reference::<Schema>(reference, parsed_spec)?;
-            Script::VisitObjectPropertyReferenceStart.call_with_descriptor(
+            if !parsed_spec
+                .enter_reference(&references::absolute_reference(reference, parsed_spec)?)
+            {
+                return visit_schema_cycle(
+                    parsed_spec,
+                    output,
+                    property_name,
+                    reference,
+                    &property_schema,
+                );
+            }
+            let directive = Script::VisitObjectPropertyReferenceStart.call_with_directive(
                 get_call_id(property_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     property_name,
                     reference,
@@ -2192,13 +2824,22 @@ pub fn visit_object_property(
                         .expect("Unable to get extensions from resolved property schema")
                         .schema_data
                         .extensions,
+                    references::resolve_reference_chain(reference, parsed_spec)?.as_slice(),
                 ),
             )?;
 
-            visit_object_property(parsed_spec, out_path, None, property_schema)?;
+            let visited = if directive != VisitDirective::SkipChildren {
+                visit_referenced(parsed_spec, reference, || {
+                    visit_object_property(parsed_spec, output, None, &property_schema)
+                })
+            } else {
+                Ok(())
+            };
+            parsed_spec.leave_reference();
+            visited?;
             Script::VisitObjectPropertyReferenceEnd.call_with_descriptor(
                 get_call_id(property_name, reference).as_deref(),
-                out_path,
+                output,
                 &(
                     property_name,
                     reference,
@@ -2213,17 +2854,17 @@ pub fn visit_object_property(
         ReferenceOr::Item(schema) => {
             let schema = schema.as_schema();
 
-            Script::VisitObjectPropertyStart.call_with_descriptor(
+            let directive = Script::VisitObjectPropertyStart.call_with_directive(
                 property_name,
-                out_path,
+                output,
                 &(property_name, schema, &schema.schema_data.extensions),
             )?;
-
-            visit_schema(parsed_spec, out_path, None, property_schema_ref)?;
-
+            if directive != VisitDirective::SkipChildren {
+                visit_schema(parsed_spec, output, None, property_schema_ref)?;
+            }
             Script::VisitObjectPropertyEnd.call_with_descriptor(
                 property_name,
-                out_path,
+                output,
                 &(property_name, schema, &schema.schema_data.extensions),
             )
         }
@@ -2232,76 +2873,84 @@ pub fn visit_object_property(
 
 pub fn visit_object(
     parsed_spec: &ParsedSpec,
-    out_path: &Path,
+    output: &VisitOutput,
     object_description: &ObjectType,
     extensions: &IndexMap<String, serde_json::Value>,
 ) -> Result<()> {
-    Script::VisitObjectStart.call_with_descriptor(
+    let directive = Script::VisitObjectStart.call_with_directive(
         None,
-        out_path,
+        output,
         &(object_description, extensions),
     )?;
-    if !object_description.properties.is_empty() {
-        Script::VisitObjectPropertiesStart.call_with_descriptor(
-            None,
-            out_path,
-            &(&object_description.properties, extensions),
-        )?;
-
-        for (local_property_name, property_schema_ref) in &object_description.properties {
-            let unboxed = property_schema_ref.clone().unbox();
-            visit_object_property(parsed_spec, out_path, Some(local_property_name), &unboxed)?;
-        }
-
-        Script::VisitObjectPropertiesEnd.call_with_descriptor(
-            None,
-            out_path,
-            &(&object_description.properties, extensions),
-        )?;
-    }
-
-    if let Some(it) = object_description.additional_properties.as_ref() {
-        match it {
-            openapiv3::AdditionalProperties::Any(value) => {
-                Script::VisitAdditionalPropertiesAny.call_with_descriptor(
-                    None,
-                    out_path,
-                    &(
-                        *value,
-                        object_description.min_properties,
-                        object_description.max_properties,
-                        extensions,
-                    ),
-                )?;
+    if directive != VisitDirective::SkipChildren {
+        if !object_description.properties.is_empty() {
+            let directive = Script::VisitObjectPropertiesStart.call_with_directive(
+                None,
+                output,
+                &(&object_description.properties, extensions),
+            )?;
+            if directive != VisitDirective::SkipChildren {
+                for (local_property_name, property_schema_ref) in &object_description.properties {
+                    let unboxed = property_schema_ref.clone().unbox();
+                    visit_located(parsed_spec, &["properties", local_property_name], || {
+                        visit_object_property(
+                            parsed_spec,
+                            output,
+                            Some(local_property_name),
+                            &unboxed,
+                        )
+                    })?;
+                }
             }
-            openapiv3::AdditionalProperties::Schema(it) => {
-                let schema_ref = it.as_ref();
-                Script::VisitAdditionalPropertiesStart.call_with_descriptor(
-                    None,
-                    out_path,
-                    &(
-                        schema_ref,
-                        object_description.min_properties,
-                        object_description.max_properties,
-                        extensions,
-                    ),
-                )?;
-
-                visit_schema(parsed_spec, out_path, None, schema_ref)?;
+            Script::VisitObjectPropertiesEnd.call_with_descriptor(
+                None,
+                output,
+                &(&object_description.properties, extensions),
+            )?;
+        }
 
-                Script::VisitAdditionalPropertiesEnd.call_with_descriptor(
-                    None,
-                    out_path,
-                    &(
-                        schema_ref,
-                        object_description.min_properties,
-                        object_description.max_properties,
-                        extensions,
-                    ),
-                )?;
+        if let Some(it) = object_description.additional_properties.as_ref() {
+            match it {
+                openapiv3::AdditionalProperties::Any(value) => {
+                    Script::VisitAdditionalPropertiesAny.call_with_descriptor(
+                        None,
+                        output,
+                        &(
+                            *value,
+                            object_description.min_properties,
+                            object_description.max_properties,
+                            extensions,
+                        ),
+                    )?;
+                }
+                openapiv3::AdditionalProperties::Schema(it) => {
+                    let schema_ref = it.as_ref();
+                    let directive = Script::VisitAdditionalPropertiesStart.call_with_directive(
+                        None,
+                        output,
+                        &(
+                            schema_ref,
+                            object_description.min_properties,
+                            object_description.max_properties,
+                            extensions,
+                        ),
+                    )?;
+                    if directive != VisitDirective::SkipChildren {
+                        visit_schema(parsed_spec, output, None, schema_ref)?;
+                    }
+                    Script::VisitAdditionalPropertiesEnd.call_with_descriptor(
+                        None,
+                        output,
+                        &(
+                            schema_ref,
+                            object_description.min_properties,
+                            object_description.max_properties,
+                            extensions,
+                        ),
+                    )?;
+                }
             }
         }
     }
-
-    Script::VisitObjectEnd.call_with_descriptor(None, out_path, &(object_description, extensions))
+    Script::VisitObjectEnd.call_with_descriptor(None, output, &(object_description, extensions))
 }
@@ -1,17 +1,276 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
+use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
+use log::debug;
+use openapiv3::{AdditionalProperties, ReferenceOr, Schema};
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-use crate::enums::common::{Script, WriteMode};
+use crate::enums::common::{InputKind, LintSeverity, Script, VisitDirective, WriteMode};
+use crate::services::{references, spec_keys};
 
 pub struct BracketScripts {
     pub start: Script,
     pub end: Script,
 }
 
+/// Header of remote requests to host with value from environment variable
+#[derive(Debug, Clone)]
+pub struct HttpHeader {
+    pub host: String,
+    pub name: String,
+    pub env_var: String,
+}
+
+/// Index of whole spec built before traversal, visitors query it as `SPEC` global of Lua.
+/// Schemas of root spec are keyed by local reference `#pointer`, others by absolute one.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecIndex {
+    pub schemas: IndexMap<String, IndexedSchema>,
+    /// Operations by operationId, or by `METHOD path` if it is absent
+    pub operations: IndexMap<String, IndexedOperation>,
+    /// Keys of operations by tag
+    pub tags: IndexMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedSchema {
+    pub reference: String,
+    /// Name of schema in components
+    pub name: Option<String>,
+    pub schema: serde_json::Value,
+    /// Schemas referenced from allOf of this schema
+    pub parents: Vec<String>,
+    /// Schemas with this schema in their allOf
+    pub children: Vec<String>,
+    /// Schemas referenced from this schema or from its inline subschemas
+    pub uses: Vec<String>,
+    pub used_by_schemas: Vec<String>,
+    pub used_by_operations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedOperation {
+    pub operation_id: Option<String>,
+    pub method: String,
+    /// Path or name of webhook
+    pub path: String,
+    pub webhook: bool,
+    pub tags: Vec<String>,
+    pub operation: serde_json::Value,
+    /// Schemas referenced from operation, its path item parameters and callbacks
+    pub uses: Vec<String>,
+}
+
+/// Filters of translated spec, set in `x-ot-filters` extension of spec or by CLI
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Filters {
+    pub include_tags: Vec<String>,
+    pub exclude_tags: Vec<String>,
+    /// Glob patterns of paths
+    pub include_paths: Vec<String>,
+    pub include_operations: Vec<String>,
+    pub include_schemas: Vec<String>,
+    /// Only component schemas reachable from included operations and schemas are translated
+    pub prune_schemas: bool,
+}
+
+pub struct Selection {
+    /// Absolute references `document#pointer` of path items and operations excluded by filters
+    pub excluded_locations: HashSet<String>,
+    /// Names of component schemas to translate, all of them if it is absent
+    pub schemas: Option<HashSet<String>>,
+}
+
+/// Keywords of OpenAPI 3.1 schema absent in OpenAPI 3.0 model, kept in x-ot-json-schema extension
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonSchemaKeywords {
+    #[serde(rename = "const", default, skip_serializing_if = "Option::is_none")]
+    pub constant: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefix_items: Vec<ReferenceOr<Schema>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_media_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unevaluated_properties: Option<AdditionalProperties>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub dependent_schemas: IndexMap<String, ReferenceOr<Schema>>,
+    #[serde(rename = "if", default, skip_serializing_if = "Option::is_none")]
+    pub if_schema: Option<ReferenceOr<Schema>>,
+    #[serde(rename = "then", default, skip_serializing_if = "Option::is_none")]
+    pub then_schema: Option<ReferenceOr<Schema>>,
+    #[serde(rename = "else", default, skip_serializing_if = "Option::is_none")]
+    pub else_schema: Option<ReferenceOr<Schema>>,
+}
+
 pub struct ParsedSpec {
     pub path: PathBuf,
     pub spec: Arc<serde_json::Value>,
+    /// Base URI of root spec, all references from root spec resolved against it
+    pub uri: Url,
+    /// Chain of schema references visited at the moment, used to found cycles
+    pub references_chain: Mutex<Vec<String>>,
+    /// Documents and resolved references of this spec, dropped together with spec
+    pub store: ReferencesStore,
+    /// Absolute URIs of `$id` and `$anchor` to document and location of identified schema in it
+    pub identifiers: Mutex<HashMap<String, (String, Vec<String>)>>,
+    /// Source positions of nodes by JSON pointer for every loaded document
+    pub locations: Mutex<HashMap<String, HashMap<String, SourcePosition>>>,
+    /// Absolute references `document#pointer` of nodes visited at the moment, last is the deepest,
+    /// shared with linter to locate its diagnostics
+    pub visited_locations: Arc<Mutex<Vec<String>>>,
+    /// Parts of spec left for translation by filters, whole spec is translated if it is absent
+    pub selection: Option<Selection>,
+    /// Bare JSON Schema document has no version, so its kind is given by translation
+    pub input_kind: InputKind,
+}
+
+/// Position in source document, merged spec has nodes from several documents
+#[derive(Debug, Clone)]
+pub struct SourcePosition {
+    pub document: Arc<str>,
+    pub line: usize,
+    pub column: usize,
+}
+
+type StoredValue = Arc<dyn Any + Send + Sync>;
+
+#[derive(Default)]
+pub struct ReferencesStore {
+    values: Mutex<HashMap<String, StoredValue>>,
+}
+
+impl ParsedSpec {
+    pub fn new(path: PathBuf, spec: serde_json::Value) -> Result<Self> {
+        let uri = references::get_spec_uri(&path)?;
+        Ok(ParsedSpec {
+            path,
+            spec: Arc::new(spec),
+            uri,
+            references_chain: Mutex::new(Vec::new()),
+            store: ReferencesStore::default(),
+            identifiers: Mutex::new(HashMap::new()),
+            locations: Mutex::new(HashMap::new()),
+            visited_locations: Arc::new(Mutex::new(Vec::new())),
+            selection: None,
+            input_kind: InputKind::Openapi,
+        })
+    }
+
+    pub fn get_references_chain(&self) -> Vec<String> {
+        self.references_chain
+            .lock()
+            .expect("Could not lock references chain")
+            .clone()
+    }
+
+    /// Returns false without changes of chain if reference already visited
+    pub fn enter_reference(&self, reference: &str) -> bool {
+        let mut chain = self
+            .references_chain
+            .lock()
+            .expect("Could not lock references chain");
+        if chain.iter().any(|it| it == reference) {
+            false
+        } else {
+            chain.push(reference.to_owned());
+            true
+        }
+    }
+
+    pub fn leave_reference(&self) {
+        self.references_chain
+            .lock()
+            .expect("Could not lock references chain")
+            .pop();
+    }
+
+    pub fn add_identifiers(&self, identifiers: HashMap<String, (String, Vec<String>)>) {
+        self.identifiers
+            .lock()
+            .expect("Could not lock identifiers")
+            .extend(identifiers);
+    }
+
+    pub fn find_identifier(&self, uri: &str) -> Option<(String, Vec<String>)> {
+        self.identifiers
+            .lock()
+            .expect("Could not lock identifiers")
+            .get(uri)
+            .cloned()
+    }
+
+    pub fn add_locations(&self, document_uri: &str, locations: HashMap<String, SourcePosition>) {
+        self.locations
+            .lock()
+            .expect("Could not lock locations")
+            .insert(document_uri.to_owned(), locations);
+    }
+
+    pub fn find_location(&self, document_uri: &str, pointer: &str) -> Option<SourcePosition> {
+        self.locations
+            .lock()
+            .expect("Could not lock locations")
+            .get(document_uri)
+            .and_then(|it| it.get(pointer))
+            .cloned()
+    }
+
+    /// Enters node by absolute reference `document#pointer`
+    pub fn enter_location(&self, location: String) {
+        self.visited_locations
+            .lock()
+            .expect("Could not lock visited locations")
+            .push(location);
+    }
+
+    /// Enters node by keys relative to last visited node or to root spec
+    pub fn enter_child_location(&self, keys: &[&str]) {
+        let mut visited = self
+            .visited_locations
+            .lock()
+            .expect("Could not lock visited locations");
+        let mut location = visited.last().cloned().unwrap_or_else(|| {
+            let mut root_uri = self.uri.clone();
+            root_uri.set_fragment(None);
+            format!("{}#", root_uri)
+        });
+        for key in keys {
+            location = spec_keys::child_location(&location, key);
+        }
+        visited.push(location);
+    }
+
+    pub fn leave_location(&self) {
+        self.visited_locations
+            .lock()
+            .expect("Could not lock visited locations")
+            .pop();
+    }
+
+    pub fn get_visited_location(&self) -> Option<String> {
+        self.visited_locations
+            .lock()
+            .expect("Could not lock visited locations")
+            .last()
+            .cloned()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,4 +278,96 @@ pub struct Code {
     pub code: Option<String>,
     pub file: String,
     pub mode: WriteMode,
+    /// Unix file mode as octal string, for example "755" for executable scripts
+    pub permissions: Option<String>,
+    /// Binary content encoded as base64, used instead of code if present
+    pub base64: Option<String>,
+}
+
+/// Element of list returned by visitor, directive is checked first since write operation has no such field
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum VisitorOutput {
+    Directive { directive: VisitDirective },
+    Code(Code),
+}
+
+/// Traversal is stopped by visitor, it is not a failure of translation
+#[derive(Debug)]
+pub struct TraversalStopped;
+
+impl Display for TraversalStopped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Traversal stopped by visitor")
+    }
+}
+
+impl std::error::Error for TraversalStopped {}
+
+/// Destination of visitors calls: Lua visitors write code to out path, linter checks their arguments by rules
+pub enum VisitOutput<'a> {
+    Code(&'a Path),
+    Lint(&'a Mutex<Linter>),
+}
+
+/// State of lint run, collects diagnostics of rules broken by arguments of visitors
+pub struct Linter {
+    pub severities: HashMap<String, LintSeverity>,
+    pub visited_locations: Arc<Mutex<Vec<String>>>,
+    pub diagnostics: Vec<LintDiagnostic>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LintDiagnostic {
+    pub rule: String,
+    pub severity: LintSeverity,
+    /// Absolute reference `document#pointer` of node visited when rule was broken
+    pub location: Option<String>,
+    pub message: String,
+}
+
+/// OpenAPI Overlay 1.0 document, only fields used to apply it
+#[derive(Debug, Clone, Deserialize)]
+pub struct Overlay {
+    pub overlay: String,
+    pub actions: Vec<OverlayAction>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OverlayAction {
+    /// JSONPath expression selecting nodes of spec
+    pub target: String,
+    pub description: Option<String>,
+    pub update: Option<serde_json::Value>,
+    #[serde(default)]
+    pub remove: bool,
+}
+
+impl ReferencesStore {
+    pub fn compute_if_absent<F, R>(&self, key: String, factory: F) -> Result<Arc<R>>
+    where
+        R: 'static + Any + Send + Sync,
+        F: FnOnce() -> Result<R>,
+    {
+        let read_values = self.values.lock().expect("Could not lock references store");
+
+        let stored = match read_values.get(&key) {
+            Some(value) => {
+                debug!("Used cached value [{}]", key);
+                value.clone()
+            }
+            None => {
+                // factory can resolve other values, so lock is not held while it works
+                drop(read_values);
+                let value: StoredValue = Arc::new(factory()?);
+                let mut write_values = self.values.lock().expect("Could not lock references store");
+                write_values.insert(key, value.clone());
+                value
+            }
+        };
+
+        stored
+            .downcast::<R>()
+            .map_err(|_| anyhow!("Could not cast value to out cached type"))
+    }
 }
@@ -0,0 +1,183 @@
+//! Fixtures of tests/fixtures are run by the binary with options of the checked feature, every
+//! written file is compared with the file of the same name in expected dir of fixture, lint
+//! fixtures fail by Lua rules with error severity
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const FIXTURES_PATH: &str = "tests/fixtures";
+const TARGET_SCRIPTS_PATH: &str = "resources/target-java-spring-boot";
+const VISITORS_SCRIPTS_PATH: &str = "resources/visitors";
+
+/// Fixture input path relative to crate root, scripts are found by Lua relative to it
+fn input(fixture: &str, file: &str) -> String {
+    format!("{}/{}/openapi/{}", FIXTURES_PATH, fixture, file)
+}
+
+fn run_fixture(fixture: &str, args: &[&str]) -> PathBuf {
+    let out_path = env::temp_dir().join(format!("openapi-translator-fixture-{}", fixture));
+    let _ = fs::remove_dir_all(&out_path);
+    fs::create_dir_all(&out_path).expect("Could not create fixture out dir");
+
+    let args: Vec<String> = args
+        .iter()
+        .map(|it| it.replace("{out}", out_path.to_str().expect("Invalid out path")))
+        .collect();
+    let output = Command::new(env!("CARGO_BIN_EXE_openapi-translator"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(&args)
+        .output()
+        .expect("Could not run openapi-translator");
+    assert!(
+        output.status.success(),
+        "Fixture [{}] failed: {}",
+        fixture,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    out_path
+}
+
+fn assert_expected(fixture: &str, out_path: &Path) {
+    let expected_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(FIXTURES_PATH)
+        .join(fixture)
+        .join("expected");
+    let file_names = |path: &Path| -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(path)
+            .unwrap_or_else(|error| panic!("Could not read [{:?}]: {}", path, error))
+            .map(|it| it.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    };
+
+    assert_eq!(
+        file_names(out_path),
+        file_names(&expected_path),
+        "Fixture [{}] wrote other files",
+        fixture
+    );
+    for name in file_names(&expected_path) {
+        assert_eq!(
+            fs::read_to_string(out_path.join(&name)).unwrap(),
+            fs::read_to_string(expected_path.join(&name)).unwrap(),
+            "Fixture [{}] file [{}] differs",
+            fixture,
+            name
+        );
+    }
+}
+
+fn translate(fixture: &str, options: &[&str], spec_args: &[&str]) {
+    let args = [
+        "--target-scripts",
+        TARGET_SCRIPTS_PATH,
+        "--visitors-scripts",
+        VISITORS_SCRIPTS_PATH,
+    ]
+    .iter()
+    .chain(options)
+    .chain(&["translate", "--out", "{out}"])
+    .chain(spec_args)
+    .copied()
+    .collect::<Vec<&str>>();
+    assert_expected(fixture, &run_fixture(fixture, &args));
+}
+
+#[test]
+fn bundle() {
+    let spec = input("bundle", "openapi.yml");
+    let out_path = run_fixture(
+        "bundle",
+        &["bundle", "--spec", &spec, "--out", "{out}/openapi.yml"],
+    );
+    assert_expected("bundle", &out_path);
+}
+
+#[test]
+fn dereference() {
+    let spec = input("dereference", "openapi.yml");
+    let out_path = run_fixture(
+        "dereference",
+        &["dereference", "--spec", &spec, "--out", "{out}/openapi.yml"],
+    );
+    assert_expected("dereference", &out_path);
+}
+
+#[test]
+fn overlay() {
+    translate(
+        "overlay",
+        &["--overlay", &input("overlay", "overlay.yml")],
+        &["--spec", &input("overlay", "openapi.yml")],
+    );
+}
+
+#[test]
+fn merge() {
+    translate(
+        "merge",
+        &[],
+        &[
+            "--spec",
+            &input("merge", "openapi.yml"),
+            "--spec",
+            &input("merge", "orders.yml"),
+            "--merge-conflict",
+            "rename",
+        ],
+    );
+}
+
+#[test]
+fn swagger_upgrade() {
+    translate(
+        "swagger-upgrade",
+        &[],
+        &["--spec", &input("swagger-upgrade", "openapi.yml")],
+    );
+}
+
+#[test]
+fn filter() {
+    translate("filter", &[], &["--spec", &input("filter", "openapi.yml")]);
+}
+
+#[test]
+fn skip_stop() {
+    let target_path = format!("{}/skip-stop/target-scripts", FIXTURES_PATH);
+    let spec = input("skip-stop", "openapi.yml");
+    let out_path = run_fixture(
+        "skip-stop",
+        &[
+            "--target-scripts",
+            &target_path,
+            "--visitors-scripts",
+            VISITORS_SCRIPTS_PATH,
+            "translate",
+            "--out",
+            "{out}",
+            "--spec",
+            &spec,
+        ],
+    );
+    assert_expected("skip-stop", &out_path);
+}
+
+#[test]
+fn index_names() {
+    let rules = format!("{}/index-names/rules.lua", FIXTURES_PATH);
+    run_fixture(
+        "index-names",
+        &[
+            "lint",
+            "--spec",
+            &input("index-names", "openapi.yml"),
+            "--lua-rules",
+            &rules,
+        ],
+    );
+}
@@ -0,0 +1,13 @@
+-- Schema B is referenced by A before its own entry of components
+return {
+  ["referenced-schema-name"] = {
+    visitor = "visitSpecStart",
+    severity = "error",
+    check = function()
+      local name = SPEC.getSchema("#/components/schemas/B").name
+      if name ~= "B" then
+        return "Schema B is indexed with name " .. tostring(name)
+      end
+    end
+  }
+}
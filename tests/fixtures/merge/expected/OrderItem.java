@@ -0,0 +1,5 @@
+
+public class OrderItem {
+
+public String name;
+}
@@ -0,0 +1,5 @@
+
+public class Pet1Owner {
+
+public String nickname;
+}
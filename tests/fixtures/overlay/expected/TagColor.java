@@ -0,0 +1,5 @@
+
+public class TagColor {
+
+public String code;
+}
@@ -0,0 +1,5 @@
+
+public class VisitedInner {
+
+public String name;
+}
@@ -0,0 +1,36 @@
+--- Java target of this fixture, schema start visitor returns directive given by x-fixture-directive extension:
+--- children of schema with "skip" are not visited, traversal stops at schema with "stop"
+
+--- code.lua and variants are taken from the Java target, script paths are relative to working dir
+package.path = "./resources/target-java-spring-boot/?.lua;" .. package.path
+
+CODE = require("code")
+VISITORS = require("cbase")
+
+local DIRECTIVES = {
+    skip = VisitDirective.SKIP_CHILDREN,
+    stop = VisitDirective.STOP
+}
+
+local visitSchemaStart = VISITORS.components.schemas.visitSchemaStart
+
+--- @param schemaName string|null #
+--- @param schemaDescriptor Schema #
+--- @param extensions table #
+--- @param callId string? #
+--- @return WriteOperation[] #
+VISITORS.components.schemas.visitSchemaStart = function(schemaName, schemaDescriptor, extensions, callId)
+    local result = visitSchemaStart(schemaName, schemaDescriptor, extensions, callId)
+    local directive = DIRECTIVES[extensions["x-fixture-directive"]]
+    if directive ~= nil then
+        table.insert(result, directive)
+    end
+    return result
+end
+
+--- @param callId string? # some useful identifier of this visitor call
+local function target(callId)
+    print("Target script called")
+end
+
+return functionCallAndLog("target", target)
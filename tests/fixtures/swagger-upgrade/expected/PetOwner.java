@@ -0,0 +1,5 @@
+
+public class PetOwner {
+
+public String name;
+}